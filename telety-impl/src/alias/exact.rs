@@ -35,6 +35,7 @@ impl<'m> quote::ToTokens for Exact<'m> {
         let ident_internal = index.ident_internal(path.friendly_path());
 
         let span = path.truncated_path.span();
+        let cfg = self.0.cfg_attr();
         let visibility = map.visibility();
         let super_visibility = syn_util::super_visibility(visibility);
         let super2_visibility = syn_util::super_visibility(&super_visibility);
@@ -52,6 +53,7 @@ impl<'m> quote::ToTokens for Exact<'m> {
 
             quote_spanned! { span =>
                 // Create a fixed alias for our submodule to reference
+                #cfg
                 #use_visibility use #alias_path as #ident;
             }
         };
@@ -59,6 +61,7 @@ impl<'m> quote::ToTokens for Exact<'m> {
             alias::Kind::Type => {
                 let parameters = &arguments.args;
                 Some(quote_spanned! { span =>
+                    #cfg
                     #super_visibility type #ident_internal #parameters = #alias_path #parameters;
                 })
             }
@@ -1,14 +1,16 @@
 use std::collections::HashMap;
 
-use quote::{TokenStreamExt as _, format_ident, quote};
+use quote::{ToTokens as _, TokenStreamExt as _, format_ident, quote};
 use syn::{parse_quote, spanned::Spanned as _};
 
-use crate::{Alias, Options, alias, syn_util, visitor};
+use crate::{Alias, Options, alias, interop, syn_util, visitor};
 
 #[derive(Debug)]
 pub(crate) struct Root {
     telety_path: Option<syn::Path>,
     map_path: syn::Path,
+    /// The [Root::map_path], using the crate name instead of the `crate::` qualifier
+    global_map_path: syn::Path,
     generics: syn::Generics,
     alias_traits: Option<bool>,
 }
@@ -38,6 +40,8 @@ pub struct Map<'p> {
     primary: Option<(alias::Path, alias::Arguments)>,
     // Maps exact type to index
     lookup: HashMap<alias::Path, (usize, alias::Arguments, alias::Kind)>,
+    // Maps exact type to the cfg predicates of the places it is used, if all of them are conditional
+    cfgs: HashMap<alias::Path, Option<Vec<syn::Meta>>>,
     // // Maps index to de-Self'ed type
     // list: Vec<alias::Path>,
 }
@@ -46,6 +50,7 @@ impl<'p> Map<'p> {
     pub(crate) fn new_root(
         telety_path: Option<syn::Path>,
         map_path: syn::Path,
+        global_map_path: syn::Path,
         module: alias::Module,
         generics: syn::Generics,
         unique_ident: syn::Ident,
@@ -54,6 +59,7 @@ impl<'p> Map<'p> {
         let root = Root {
             telety_path,
             map_path,
+            global_map_path,
             generics,
             alias_traits: options.alias_traits,
         };
@@ -66,6 +72,7 @@ impl<'p> Map<'p> {
             unique_ident,
             primary: None,
             lookup: HashMap::new(),
+            cfgs: HashMap::new(),
             // list: vec![]
         }
     }
@@ -82,6 +89,7 @@ impl<'p> Map<'p> {
             unique_ident,
             primary: None,
             lookup: HashMap::new(),
+            cfgs: HashMap::new(),
             // list: vec![],
         }
     }
@@ -165,6 +173,98 @@ impl<'p> Map<'p> {
     }
 }
 
+impl Map<'static> {
+    /// Recreate a [Map] from [interop::AliasMapArgs], such as the output of the `alias_map` command.
+    /// Unlike recreating the [Map] from the item definition, this does not depend on the consumer and producer
+    /// of the telety information identifying aliases in the same way.
+    pub fn from_args(args: interop::AliasMapArgs) -> syn::Result<Self> {
+        let interop::AliasMapArgs {
+            mut map_path,
+            vis,
+            unique_ident,
+            mut generics,
+            where_clause,
+            telety_path,
+            alias_traits,
+            self_type,
+            aliased_types,
+            ..
+        } = args;
+
+        let Some(module_segment) = map_path.segments.pop() else {
+            return Err(syn::Error::new(map_path.span(), "Expected a module path"));
+        };
+        let module_segment = module_segment.into_value();
+        // Remove the trailing `::`
+        if let Some(last) = map_path.segments.pop() {
+            map_path.segments.push(last.into_value());
+        }
+
+        let global_map_path = map_path.clone();
+        let mut crateify = visitor::Crateify::try_new()?;
+        directed_visit::visit_mut(
+            &mut directed_visit::syn::direct::FullDefault,
+            &mut crateify,
+            &mut map_path,
+        );
+
+        generics.where_clause = where_clause;
+
+        let root = Root {
+            telety_path,
+            map_path,
+            global_map_path,
+            generics,
+            alias_traits: alias_traits.map(|value| value.value),
+        };
+
+        let mut map = Self {
+            root: OwnedOrRef::Owned(root),
+            parent: None,
+
+            module: alias::Module::new(vis, module_segment.ident),
+            unique_ident,
+            primary: None,
+            lookup: HashMap::new(),
+            cfgs: HashMap::new(),
+        };
+
+        if let Some(self_type) = self_type {
+            let syn::Type::Path(mut self_type) = self_type else {
+                return Err(syn::Error::new(self_type.span(), "Expected a type path"));
+            };
+            directed_visit::visit_mut(
+                &mut directed_visit::syn::direct::FullDefault,
+                &mut crateify,
+                &mut self_type.path,
+            );
+            map.set_self(&self_type.path)?;
+        }
+
+        // Types are in index order, so inserting them recreates the same aliases
+        for aliased_type in &aliased_types {
+            let (path, kind) = match aliased_type {
+                syn::Type::Path(type_path) => (&type_path.path, alias::Kind::Type),
+                syn::Type::TraitObject(syn::TypeTraitObject { bounds, .. }) => {
+                    let Some(syn::TypeParamBound::Trait(bound)) = bounds.first() else {
+                        return Err(syn::Error::new(bounds.span(), "Expected a trait path"));
+                    };
+                    (&bound.path, alias::Kind::Trait)
+                }
+                aliased_type => {
+                    return Err(syn::Error::new(
+                        aliased_type.span(),
+                        "Expected a type path or `dyn` trait path",
+                    ));
+                }
+            };
+            map.insert(path, kind, None)?;
+        }
+
+        Ok(map)
+    }
+}
+
 impl<'p> Map<'p> {
     pub fn new_sub_map(&self, suffix: &str) -> Map<'_> {
         Map::new_child(self, suffix)
@@ -198,6 +298,31 @@ impl<'p> Map<'p> {
     /// already exists in the map, this is a no-op. Maps constructed with the same parameters and order of
     /// inserts will yield the same [Alias]es.
     pub fn insert_type(&mut self, ty: &syn::TypePath) -> Result<bool, alias::Error> {
+        self.insert_type_with_mode(ty, alias::Mode::Default)
+    }
+
+    /// Register a [syn::TypePath] in the [Map], as [Map::insert_type], unless `mode` excludes it.
+    /// [alias::Mode::Skip] and [alias::Mode::Builtin] types are not registered.
+    pub fn insert_type_with_mode(
+        &mut self,
+        ty: &syn::TypePath,
+        mode: alias::Mode,
+    ) -> Result<bool, alias::Error> {
+        self.insert_type_with_cfg(ty, mode, None)
+    }
+
+    /// Register a [syn::TypePath] in the [Map], as [Map::insert_type_with_mode], used where `cfg` is true.
+    /// If a type is only used conditionally, its alias is only generated when any of those conditions is true.
+    pub fn insert_type_with_cfg(
+        &mut self,
+        ty: &syn::TypePath,
+        mode: alias::Mode,
+        cfg: Option<&syn::Meta>,
+    ) -> Result<bool, alias::Error> {
+        if matches!(mode, alias::Mode::Skip | alias::Mode::Builtin) {
+            return Ok(false);
+        }
+
         // Associated types are not supported (watch https://github.com/rust-lang/rust/issues/134691)
         if ty.qself.is_some() {
             return Err(alias::Error::new(
@@ -205,31 +330,74 @@ impl<'p> Map<'p> {
                 alias::error::Kind::AssociatedType,
             ));
         }
-        self.insert(&ty.path, alias::Kind::Type)
+        self.insert(&ty.path, alias::Kind::Type, cfg)
     }
 
     /// Register a [syn::Path] to a trait in the [Map]. If the exact (i.e. identical tokens, not equivalent Rust types) type
     /// already exists in the map, this is a no-op. Maps constructed with the same parameters and order of
     /// inserts will yield the same [Alias]es.
     pub fn insert_trait(&mut self, ty: &syn::Path) -> Result<bool, alias::Error> {
-        let can_insert = self
-            .root()
-            .alias_traits
-            .unwrap_or_else(|| ty.leading_colon.is_some() || ty.segments.len() > 1);
+        self.insert_trait_with_mode(ty, alias::Mode::Default)
+    }
+
+    /// Register a [syn::Path] to a trait in the [Map], as [Map::insert_trait], unless `mode` excludes it.
+    /// [alias::Mode::Skip] and [alias::Mode::Builtin] traits are not registered.
+    /// [alias::Mode::Alias] traits are registered regardless of the `alias_traits` option.
+    pub fn insert_trait_with_mode(
+        &mut self,
+        ty: &syn::Path,
+        mode: alias::Mode,
+    ) -> Result<bool, alias::Error> {
+        self.insert_trait_with_cfg(ty, mode, None)
+    }
+
+    /// Register a [syn::Path] to a trait in the [Map], as [Map::insert_trait_with_mode], used where `cfg` is true.
+    /// If a trait is only used conditionally, its alias is only generated when any of those conditions is true.
+    pub fn insert_trait_with_cfg(
+        &mut self,
+        ty: &syn::Path,
+        mode: alias::Mode,
+        cfg: Option<&syn::Meta>,
+    ) -> Result<bool, alias::Error> {
+        let can_insert = match mode {
+            alias::Mode::Skip | alias::Mode::Builtin => return Ok(false),
+            alias::Mode::Alias => true,
+            alias::Mode::Default => self
+                .root()
+                .alias_traits
+                .unwrap_or_else(|| ty.leading_colon.is_some() || ty.segments.len() > 1),
+        };
 
         if can_insert {
-            self.insert(ty, alias::Kind::Trait)
+            self.insert(ty, alias::Kind::Trait, cfg)
         } else {
             Err(alias::Error::new(ty.span(), alias::error::Kind::Trait))
         }
     }
 
-    fn insert(&mut self, ty: &syn::Path, alias_type: alias::Kind) -> Result<bool, alias::Error> {
+    fn insert(
+        &mut self,
+        ty: &syn::Path,
+        alias_type: alias::Kind,
+        cfg: Option<&syn::Meta>,
+    ) -> Result<bool, alias::Error> {
         if ty.is_ident("Self") {
             // TODO should this be an error instead?
             Ok(false)
         } else if self.full_lookup(ty)?.is_some() {
             // Path already exists
+            let (path, _args) = alias::Path::new(ty)?;
+            if let Some(cfgs) = self.cfgs.get_mut(&path) {
+                match (cfgs.as_mut(), cfg) {
+                    (Some(cfgs), Some(cfg)) => {
+                        if !cfgs.contains(cfg) {
+                            cfgs.push(cfg.clone());
+                        }
+                    }
+                    (_, None) => *cfgs = None,
+                    (None, Some(_)) => {}
+                }
+            }
             Ok(false)
         } else {
             let (path, mut args) = alias::Path::new(ty)?;
@@ -237,12 +405,23 @@ impl<'p> Map<'p> {
             let index = self.lookup.len();
             args.parameterize();
 
+            self.cfgs
+                .insert(path.clone(), cfg.map(|cfg| vec![cfg.clone()]));
             self.lookup.insert(path, (index, args, alias_type));
 
             Ok(true)
         }
     }
 
+    /// The predicate under which the alias for `path` is generated, if it is only used conditionally
+    pub(crate) fn cfg(&self, path: &alias::Path) -> Option<syn::Meta> {
+        self.cfgs
+            .get(path)
+            .cloned()
+            .flatten()
+            .and_then(crate::cfg::any)
+    }
+
     pub fn get_self(&self) -> Option<Alias<'_>> {
         if let Some(alias) = self.local_get_self() {
             Some(alias)
@@ -264,6 +443,94 @@ impl<'p> Map<'p> {
         }
     }
 
+    /// Describe this [Map] (excluding any parent maps) as [interop::AliasMapArgs], which can be
+    /// recreated with [Map::from_args]. Paths use the crate name instead of the `crate::` qualifier.
+    /// Traits are described as `dyn` types.
+    pub fn to_args(&self) -> interop::AliasMapArgs {
+        let module_ident = self.module.ident();
+        let map_path: syn::Path = {
+            let map_path = &self.root().global_map_path;
+            parse_quote!(#map_path::#module_ident)
+        };
+
+        let with_arguments = |path: &alias::Path, arguments: &alias::Arguments| {
+            let mut path = path.truncated_path.clone();
+            if let (Some(last), Some(args)) = (path.segments.last_mut(), &arguments.args) {
+                last.arguments = syn::PathArguments::AngleBracketed(args.clone());
+            }
+            path
+        };
+
+        // The primary alias is the item itself, in the module of the map
+        let self_type = self.primary.as_ref().map(|(path, arguments)| {
+            let path = with_arguments(path, arguments);
+            let item_segment = path.segments.last();
+            let global_map_path = &self.root().global_map_path;
+            parse_quote!(#global_map_path::#item_segment)
+        });
+
+        let mut secondary: Vec<_> = self.lookup.iter().collect();
+        secondary.sort_by_key(|(_path, (index, _arguments, _kind))| *index);
+        let aliased_types = secondary
+            .into_iter()
+            .map(|(path, (_index, arguments, kind))| {
+                let path = with_arguments(path, arguments);
+                match kind {
+                    alias::Kind::Type => parse_quote!(#path),
+                    alias::Kind::Trait => parse_quote!(dyn #path),
+                }
+            })
+            .collect();
+
+        interop::AliasMapArgs::new(
+            map_path,
+            self.visibility().clone(),
+            self.unique_ident.clone(),
+            self.generics().clone(),
+            self.telety_path().cloned(),
+            self_type,
+            aliased_types,
+        )
+        .with_alias_traits(self.root().alias_traits)
+    }
+
+    /// A hash of the [Alias]es at this map level, in index order.
+    /// Maps which would generate different [Alias]es for the same item have different fingerprints.
+    /// The path of the primary alias depends on the crate reading the item (`crate::` in its own crate),
+    /// so only its arguments are included. Definitions from a telety-generated macro have `$crate` in place of
+    /// `crate`, so they are hashed the same.
+    pub fn fingerprint(&self) -> u64 {
+        let mut aliases: Vec<_> = self.iter_aliases().collect();
+        aliases.sort_by_key(|alias| match alias.index {
+            alias::Index::Primary => None,
+            alias::Index::Secondary(index) => Some(index),
+        });
+
+        let mut hasher = syn_util::TokenHasher::new();
+        for alias in aliases {
+            let path = match alias.index {
+                alias::Index::Primary => quote!(Self),
+                alias::Index::Secondary(_) => {
+                    let mut path = alias.aliased_path().clone();
+                    if let Some(first) = path.segments.first_mut()
+                        && syn_util::is_dollar_crate(&first.ident)
+                    {
+                        first.ident = syn::Ident::new("crate", first.ident.span());
+                    }
+                    path.into_token_stream()
+                }
+            };
+            let arguments = alias.arguments.args.as_ref();
+            let kind = match alias.kind {
+                alias::Kind::Type => quote!(type),
+                alias::Kind::Trait => quote!(trait),
+            };
+            hasher.write(quote!(#kind #path #arguments;));
+        }
+
+        hasher.finish()
+    }
+
     pub fn visitor(&self) -> visitor::ApplyAliases<'_> {
         visitor::ApplyAliases::new(self)
     }
@@ -10,6 +10,8 @@ mod kind;
 pub use kind::Kind;
 mod map;
 pub use map::Map;
+mod mode;
+pub use mode::Mode;
 mod module;
 pub use module::Module;
 mod public;
@@ -78,6 +80,20 @@ impl<'map> Alias<'map> {
         self.kind
     }
 
+    /// The `cfg` predicate under which this alias exists, if the type is only used conditionally
+    pub fn cfg(&self) -> Option<syn::Meta> {
+        match self.index {
+            // Self always exists
+            Index::Primary => None,
+            Index::Secondary(_) => self.map.cfg(self.path),
+        }
+    }
+
+    /// `#[cfg(...)]` for each item generated for this alias
+    pub(crate) fn cfg_attr(&self) -> Option<syn::Attribute> {
+        self.cfg().map(|cfg| parse_quote!(#[cfg(#cfg)]))
+    }
+
     pub(crate) fn exact(self) -> Exact<'map> {
         Exact::new(self)
     }
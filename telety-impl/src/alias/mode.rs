@@ -0,0 +1,54 @@
+use syn::{Attribute, Ident, spanned::Spanned as _};
+
+/// How the types within part of an item are aliased. Set by an inner `#[telety(...)]` attribute
+/// on a field, variant, associated item, generic parameter, or function argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Alias every type which can be aliased, and ignore those which cannot.
+    #[default]
+    Default,
+    /// `#[telety(skip)]` - Do not alias any types (e.g. private helper types).
+    Skip,
+    /// `#[telety(alias)]` - Alias every type, including traits regardless of `alias_traits`.
+    /// Types which cannot be aliased are an error.
+    Alias,
+    /// `#[telety(builtin)]` - The outermost types are available everywhere (e.g. a type from the prelude),
+    /// so do not alias them. Their generic arguments are still aliased.
+    Builtin,
+}
+
+impl Mode {
+    /// Find the [Mode] set by a `#[telety(...)]` attribute in `attrs`, if any.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Option<Self>> {
+        let mut mode = None;
+        for attr in attrs.iter().filter(|attr| Self::is_mode_attribute(attr)) {
+            let ident: Ident = attr.parse_args()?;
+            let parsed = if ident == "skip" {
+                Self::Skip
+            } else if ident == "alias" {
+                Self::Alias
+            } else if ident == "builtin" {
+                Self::Builtin
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("Unknown mode '{ident}', expected one of: 'skip', 'alias', 'builtin'"),
+                ));
+            };
+
+            if mode.replace(parsed).is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "Only one telety attribute is allowed here",
+                ));
+            }
+        }
+
+        Ok(mode)
+    }
+
+    /// Whether `attr` is a `#[telety(...)]` attribute setting a [Mode].
+    pub fn is_mode_attribute(attr: &Attribute) -> bool {
+        attr.path().is_ident("telety")
+    }
+}
@@ -27,6 +27,10 @@ impl Module {
         Ok(Self { visibility, ident })
     }
 
+    pub(crate) fn new(visibility: syn::Visibility, ident: syn::Ident) -> Self {
+        Self { visibility, ident }
+    }
+
     pub fn visibility(&self) -> &syn::Visibility {
         &self.visibility
     }
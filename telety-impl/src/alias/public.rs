@@ -4,7 +4,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::{TokenStreamExt as _, format_ident, quote, quote_spanned};
 use syn::{parse_quote, spanned::Spanned as _};
 
-use crate::{Alias, alias, syn_util, version};
+use crate::{Alias, alias, manifest, syn_util, version};
 
 #[repr(transparent)]
 #[derive(Debug)]
@@ -41,6 +41,8 @@ impl<'map> quote::ToTokens for Public<'map> {
             // or the visibility workaround
             let use_vis_workaround = kind != alias::Kind::Trait;
 
+            let cfg = self.0.cfg_attr();
+
             let ident_internal = index.ident_internal(path.friendly_path());
 
             let aliased_path = &path.truncated_path;
@@ -58,7 +60,7 @@ impl<'map> quote::ToTokens for Public<'map> {
             let telety_path_override = map.telety_path();
             let telety_path = telety_path_override
                 .map(Cow::Borrowed)
-                .unwrap_or_else(|| Cow::Owned(parse_quote!(::telety)));
+                .unwrap_or_else(|| Cow::Owned(manifest::telety_path()));
 
             if use_vis_workaround {
                 // We are allowed to export items *in* private modules at their original visibility. e.g.
@@ -99,14 +101,30 @@ impl<'map> quote::ToTokens for Public<'map> {
                             exported_apply.with_telety_path(telety_path_override.clone());
                     }
 
+                    // The apply expands to several items, which must all share the `cfg`
+                    let exported_apply = match &cfg {
+                        Some(cfg) => {
+                            match syn::parse2::<syn::File>(exported_apply.to_token_stream()) {
+                                Ok(file) => {
+                                    let items = file.items.iter();
+                                    quote!(#(#cfg #items)*)
+                                }
+                                Err(error) => error.into_compile_error(),
+                            }
+                        }
+                        None => exported_apply.to_token_stream(),
+                    };
+
                     quote! {
                         // Create an exported macro. If the type's macro existed, it is a forwarder.
                         // If it did not exist, it is a noop
                         #exported_apply
 
                         // Create an alias for just the type
+                        #cfg
                         #super_visibility type #alias_unique_ident #parameters = self::exact::#ident_internal #parameters;
 
+                        #cfg
                         #super_visibility use #alias_unique_ident as #ident;
                     }
                 } else {
@@ -115,6 +133,7 @@ impl<'map> quote::ToTokens for Public<'map> {
 
                     quote_spanned! { span =>
                         // Setup for a glob import
+                        #cfg
                         mod #submodule_ident {
                             #super2_visibility use super::exact::#ident as #ident;
 
@@ -126,6 +145,7 @@ impl<'map> quote::ToTokens for Public<'map> {
                             }
                         }
 
+                        #cfg
                         #super_visibility use #submodule_ident::globbed::#ident;
                     }
                 }
@@ -135,6 +155,7 @@ impl<'map> quote::ToTokens for Public<'map> {
                 // aliasing this trait, so they can deal with the compile error if the
                 // visibility is incorrect.
                 quote_spanned! { span =>
+                    #cfg
                     #super_visibility use self::exact::#ident as #ident;
                 }
             }
@@ -0,0 +1,213 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, format_ident};
+use syn::{Ident, Path};
+
+use crate::{Command, find_and_replace, syn_util};
+
+struct Entry {
+    macro_path: Path,
+    command: &'static Command,
+    fallback: Option<TokenStream>,
+}
+
+/// Applies [Command]s to several telety-generated macros, nesting the invocations so
+/// the final haystack receives the output of every [Command].
+/// Needles are generated automatically, and are provided to [Batch::invoke] in the order the entries were added.
+/// ## Example
+/// ```rust,ignore
+/// Batch::new()
+///     .add(path_to_struct0, &v1::TY)
+///     .add(path_to_struct1, &v1::TY)
+///     .invoke(|needles| {
+///         let [needle0, needle1] = needles else { unreachable!() };
+///         quote! {
+///             ::my_crate::mix_impl!(#needle0, #needle1, #new_struct_ident);
+///         }
+///     })
+/// ```
+#[derive(Default)]
+pub struct Batch {
+    entries: Vec<Entry>,
+    telety_path: Option<Path>,
+    unique_macro_ident: Option<Ident>,
+    auto_macro_forwarding: bool,
+}
+
+impl Batch {
+    /// Create an empty [Batch]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `command` to the telety-generated macro at `macro_path`.
+    /// `macro_path` must point to a valid telety-generated macro, otherwise a compile error will occur.
+    pub fn add(mut self, macro_path: Path, command: &'static Command) -> Self {
+        self.entries.push(Entry {
+            macro_path,
+            command,
+            fallback: None,
+        });
+        self
+    }
+
+    /// Apply `command` to the telety-generated macro at `macro_path`.
+    /// If `macro_path` does not contain a macro, the needle for this entry is replaced with `fallback` instead.
+    /// See [Apply::with_fallback](crate::Apply::with_fallback) for the limitations of fallbacks.
+    pub fn add_with_fallback(
+        mut self,
+        macro_path: Path,
+        command: &'static Command,
+        fallback: impl ToTokens,
+    ) -> Self {
+        self.entries.push(Entry {
+            macro_path,
+            command,
+            fallback: Some(fallback.into_token_stream()),
+        });
+        self
+    }
+
+    /// Specify the location of the telety crate.
+    /// See [Apply::with_telety_path](crate::Apply::with_telety_path).
+    pub fn with_telety_path(mut self, telety_path: Path) -> Self {
+        self.telety_path.replace(telety_path);
+        self
+    }
+
+    /// Forward the output of entries with fallbacks through macros, so that they are evaluated without
+    /// additional block scopes. Each entry with a fallback uses `unique_macro_ident` with its index as a suffix.
+    /// See [Apply::with_macro_forwarding](crate::Apply::with_macro_forwarding).
+    pub fn with_macro_forwarding(mut self, unique_macro_ident: Ident) -> Self {
+        self.unique_macro_ident.replace(unique_macro_ident);
+        self
+    }
+
+    /// Forward the output of entries with fallbacks through macros, generating a unique identifier for each.
+    /// See [Apply::with_auto_macro_forwarding](crate::Apply::with_auto_macro_forwarding).
+    pub fn with_auto_macro_forwarding(mut self) -> Self {
+        self.auto_macro_forwarding = true;
+        self
+    }
+
+    /// The number of entries added to the [Batch]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no entries have been added to the [Batch]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Generate the nested [Command] invocations. `haystack` is given one needle for each entry,
+    /// in the order the entries were added, and returns the tokens the needles will be replaced in.  
+    /// The needles are only valid within the returned tokens.
+    pub fn invoke(self, haystack: impl FnOnce(&[Ident]) -> TokenStream) -> TokenStream {
+        let Self {
+            entries,
+            telety_path,
+            unique_macro_ident,
+            auto_macro_forwarding,
+        } = self;
+
+        // The haystack is generated with placeholder needles, and the needles are then named after a hash
+        // of the inputs. This distinguishes the needles of separate batches, in case one batch is nested in
+        // the haystack of another, while keeping the output independent of other batches.
+        let placeholders: Vec<Ident> = (0..entries.len())
+            .map(|index| format_ident!("__telety_batch_needle_{index}"))
+            .collect();
+        let mut output = haystack(&placeholders);
+
+        let mut hasher = syn_util::TokenHasher::new();
+        for entry in &entries {
+            hasher.write(entry.macro_path.to_token_stream());
+            hasher.write(entry.command.prefix(Span::call_site()));
+            if let Some(fallback) = &entry.fallback {
+                hasher.write(fallback.clone());
+            }
+        }
+        hasher.write(output.clone());
+        let hash = hasher.finish();
+
+        let needles: Vec<Ident> = (0..entries.len())
+            .map(|index| format_ident!("__telety_batch_{hash:016x}_needle_{index}"))
+            .collect();
+        let pairs: Vec<_> = placeholders
+            .into_iter()
+            .zip(&needles)
+            .map(|(placeholder, needle)| (placeholder.into(), needle.to_token_stream()))
+            .collect();
+        output = find_and_replace::find_and_replace_multi(&pairs, output);
+
+        // Build from the inside out, so the first entry is the outermost invocation
+        for (index, (entry, needle)) in entries.into_iter().zip(needles).enumerate().rev() {
+            let Entry {
+                macro_path,
+                command,
+                fallback,
+            } = entry;
+
+            let mut apply = command.apply(macro_path, needle.clone(), &output);
+
+            if let Some(fallback) = fallback {
+                let fallback = find_and_replace::find_and_replace(needle, fallback, output);
+                apply = apply.with_fallback(fallback);
+
+                if let Some(unique_macro_ident) = &unique_macro_ident {
+                    apply =
+                        apply.with_macro_forwarding(format_ident!("{unique_macro_ident}_{index}"));
+                } else if auto_macro_forwarding {
+                    apply = apply.with_auto_macro_forwarding();
+                }
+            }
+
+            if let Some(telety_path) = &telety_path {
+                apply = apply.with_telety_path(telety_path.clone());
+            }
+
+            output = apply.into_token_stream();
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::*;
+    use crate::version::v0;
+
+    fn batch(haystack: TokenStream) -> TokenStream {
+        Batch::new()
+            .add(parse_quote!(crate::A), &v0::PATH)
+            .add_with_fallback(parse_quote!(crate::B), &v0::PATH, quote!(fallback))
+            .invoke(|needles| quote!(#(#needles)* #haystack))
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(
+            batch(quote!(haystack)).to_string(),
+            batch(quote!(haystack)).to_string()
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let inner = batch(quote!(inner));
+        let outer = batch(inner.clone());
+
+        let needle = |tokens: &TokenStream| {
+            tokens
+                .to_string()
+                .split_whitespace()
+                .find(|token| token.starts_with("__telety_batch_"))
+                .map(str::to_string)
+        };
+        assert!(needle(&inner).is_some());
+        assert_ne!(needle(&inner), needle(&outer));
+    }
+}
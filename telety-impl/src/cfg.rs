@@ -0,0 +1,249 @@
+//! Conditional compilation predicates of the parts of an item.
+//! The telety attribute sees the item before `#[cfg]`s are evaluated, so fields, variants and
+//! associated items may not exist in the compiled item.
+
+use syn::{
+    Attribute, Fields, ImplItem, Item, Meta, Token, TraitItem, parse_quote, punctuated::Punctuated,
+};
+
+/// The predicate under which something with `attrs` exists, from its `#[cfg(...)]` attributes
+/// and `#[cfg(...)]`s nested in `#[cfg_attr(...)]`. [None] if it is unconditional.
+pub(crate) fn predicate(attrs: &[Attribute]) -> syn::Result<Option<Meta>> {
+    let mut predicates = vec![];
+    for attr in attrs {
+        if attr.path().is_ident("cfg") {
+            predicates.push(attr.parse_args()?);
+        } else if attr.path().is_ident("cfg_attr") {
+            let (condition, cfgs) = parse_cfg_attr(attr)?;
+            if let Some(cfg) = all(cfgs) {
+                predicates.push(parse_quote!(any(not(#condition), #cfg)));
+            }
+        }
+    }
+
+    Ok(all(predicates))
+}
+
+/// Combine `predicates` so all must be true
+pub(crate) fn all(predicates: impl IntoIterator<Item = Meta>) -> Option<Meta> {
+    combine(predicates, |predicates| parse_quote!(all(#(#predicates),*)))
+}
+
+/// Combine `predicates` so any must be true
+pub(crate) fn any(predicates: impl IntoIterator<Item = Meta>) -> Option<Meta> {
+    combine(predicates, |predicates| parse_quote!(any(#(#predicates),*)))
+}
+
+fn combine(
+    predicates: impl IntoIterator<Item = Meta>,
+    combinator: impl FnOnce(Vec<Meta>) -> Meta,
+) -> Option<Meta> {
+    let mut predicates: Vec<_> = predicates.into_iter().collect();
+    match predicates.len() {
+        0 => None,
+        1 => predicates.pop(),
+        _ => Some(combinator(predicates)),
+    }
+}
+
+/// The condition of a `#[cfg_attr(...)]`, and the predicates of the `cfg`s it applies
+fn parse_cfg_attr(attr: &Attribute) -> syn::Result<(Meta, Vec<Meta>)> {
+    let mut metas = attr
+        .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?
+        .into_iter();
+    let Some(condition) = metas.next() else {
+        return Err(syn::Error::new_spanned(attr, "Expected a predicate"));
+    };
+    let cfgs = metas
+        .filter(|meta| meta.path().is_ident("cfg"))
+        .map(|meta| meta.require_list()?.parse_args())
+        .collect::<syn::Result<_>>()?;
+
+    Ok((condition, cfgs))
+}
+
+/// The distinct predicates of the fields, variants and associated items of `item`, in order of appearance.
+pub(crate) fn item_predicates(item: &Item) -> syn::Result<Vec<Meta>> {
+    let mut predicates = vec![];
+    for attrs in element_attrs(item) {
+        if let Some(predicate) = predicate(attrs)?
+            && !predicates.contains(&predicate)
+        {
+            predicates.push(predicate);
+        }
+    }
+
+    Ok(predicates)
+}
+
+fn element_attrs(item: &Item) -> Vec<&Vec<Attribute>> {
+    fn fields(fields: &Fields) -> Vec<&Vec<Attribute>> {
+        fields.iter().map(|field| &field.attrs).collect()
+    }
+
+    match item {
+        Item::Struct(item_struct) => fields(&item_struct.fields),
+        Item::Union(item_union) => item_union
+            .fields
+            .named
+            .iter()
+            .map(|field| &field.attrs)
+            .collect(),
+        Item::Enum(item_enum) => item_enum
+            .variants
+            .iter()
+            .flat_map(|variant| {
+                let mut attrs = vec![&variant.attrs];
+                attrs.extend(fields(&variant.fields));
+                attrs
+            })
+            .collect(),
+        Item::Trait(item_trait) => item_trait
+            .items
+            .iter()
+            .filter_map(trait_item_attrs)
+            .collect(),
+        Item::Impl(item_impl) => item_impl.items.iter().filter_map(impl_item_attrs).collect(),
+        _ => vec![],
+    }
+}
+
+pub(crate) fn trait_item_attrs(trait_item: &TraitItem) -> Option<&Vec<Attribute>> {
+    match trait_item {
+        TraitItem::Const(item) => Some(&item.attrs),
+        TraitItem::Fn(item) => Some(&item.attrs),
+        TraitItem::Type(item) => Some(&item.attrs),
+        TraitItem::Macro(item) => Some(&item.attrs),
+        _ => None,
+    }
+}
+
+pub(crate) fn impl_item_attrs(impl_item: &ImplItem) -> Option<&Vec<Attribute>> {
+    match impl_item {
+        ImplItem::Const(item) => Some(&item.attrs),
+        ImplItem::Fn(item) => Some(&item.attrs),
+        ImplItem::Type(item) => Some(&item.attrs),
+        ImplItem::Macro(item) => Some(&item.attrs),
+        _ => None,
+    }
+}
+
+pub(crate) fn trait_item_attrs_mut(trait_item: &mut TraitItem) -> Option<&mut Vec<Attribute>> {
+    match trait_item {
+        TraitItem::Const(item) => Some(&mut item.attrs),
+        TraitItem::Fn(item) => Some(&mut item.attrs),
+        TraitItem::Type(item) => Some(&mut item.attrs),
+        TraitItem::Macro(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+pub(crate) fn impl_item_attrs_mut(impl_item: &mut ImplItem) -> Option<&mut Vec<Attribute>> {
+    match impl_item {
+        ImplItem::Const(item) => Some(&mut item.attrs),
+        ImplItem::Fn(item) => Some(&mut item.attrs),
+        ImplItem::Type(item) => Some(&mut item.attrs),
+        ImplItem::Macro(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+/// Resolves the `#[cfg]`s of `item`'s fields, variants and associated items, given the value of each of
+/// `predicates` (see [item_predicates]). Disabled parts are removed, and the `cfg`s of enabled parts are removed.
+pub(crate) fn resolve(item: &Item, predicates: &[Meta], values: &[bool]) -> syn::Result<Item> {
+    if predicates.len() != values.len() {
+        return Err(syn::Error::new_spanned(
+            item,
+            format!(
+                "Expected {} cfg values, found {}",
+                predicates.len(),
+                values.len()
+            ),
+        ));
+    }
+
+    let mut error = None;
+    // Strips the cfgs of enabled parts, and returns whether the part is enabled
+    let mut keep = |attrs: &mut Vec<Attribute>| -> bool {
+        let enabled = match predicate(attrs) {
+            Ok(None) => true,
+            Ok(Some(predicate)) => predicates
+                .iter()
+                .position(|p| *p == predicate)
+                .is_some_and(|index| values[index]),
+            Err(e) => {
+                error.get_or_insert(e);
+                true
+            }
+        };
+        if enabled {
+            strip(attrs);
+        }
+        enabled
+    };
+
+    let mut item = item.clone();
+    match &mut item {
+        Item::Struct(item_struct) => retain_fields(&mut item_struct.fields, &mut keep),
+        Item::Union(item_union) => {
+            retain(&mut item_union.fields.named, |field| keep(&mut field.attrs));
+        }
+        Item::Enum(item_enum) => retain(&mut item_enum.variants, |variant| {
+            let enabled = keep(&mut variant.attrs);
+            retain_fields(&mut variant.fields, &mut keep);
+            enabled
+        }),
+        Item::Trait(item_trait) => item_trait
+            .items
+            .retain_mut(|trait_item| trait_item_attrs_mut(trait_item).is_none_or(&mut keep)),
+        Item::Impl(item_impl) => item_impl
+            .items
+            .retain_mut(|impl_item| impl_item_attrs_mut(impl_item).is_none_or(&mut keep)),
+        _ => {}
+    }
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(item),
+    }
+}
+
+fn retain_fields(fields: &mut Fields, keep: &mut impl FnMut(&mut Vec<Attribute>) -> bool) {
+    match fields {
+        Fields::Named(fields_named) => {
+            retain(&mut fields_named.named, |field| keep(&mut field.attrs))
+        }
+        Fields::Unnamed(fields_unnamed) => {
+            retain(&mut fields_unnamed.unnamed, |field| keep(&mut field.attrs))
+        }
+        Fields::Unit => {}
+    }
+}
+
+fn retain<T, P: Default>(punctuated: &mut Punctuated<T, P>, mut keep: impl FnMut(&mut T) -> bool) {
+    *punctuated = std::mem::take(punctuated)
+        .into_iter()
+        .filter_map(|mut value| keep(&mut value).then_some(value))
+        .collect();
+}
+
+/// Remove `#[cfg]`s, and the `cfg`s in `#[cfg_attr]`s
+fn strip(attrs: &mut Vec<Attribute>) {
+    attrs.retain_mut(|attr| {
+        if attr.path().is_ident("cfg") {
+            return false;
+        }
+        if attr.path().is_ident("cfg_attr")
+            && let Ok(metas) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        {
+            let mut metas = metas.into_iter();
+            let condition = metas.next();
+            let rest: Vec<_> = metas.filter(|meta| !meta.path().is_ident("cfg")).collect();
+            if rest.is_empty() {
+                return false;
+            }
+            *attr = parse_quote!(#[cfg_attr(#condition, #(#rest),*)]);
+        }
+        true
+    });
+}
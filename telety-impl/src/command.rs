@@ -1,40 +1,176 @@
 use std::borrow::Cow;
 
 use proc_macro2::{Punct, Spacing, Span, TokenStream};
-use quote::{ToTokens, format_ident, quote, quote_spanned};
-use syn::{Ident, LitInt, Path, parse_quote, parse_quote_spanned, spanned::Spanned as _};
+use quote::{ToTokens, TokenStreamExt as _, format_ident, quote, quote_spanned};
+use syn::{Ident, LitInt, Path, ext::IdentExt as _, parse_quote_spanned, spanned::Spanned as _};
 
-use crate::{Telety, find_and_replace::SingleToken};
+use crate::{
+    Telety,
+    find_and_replace::SingleToken,
+    manifest, syn_util,
+    version::{self, v0},
+};
 
-pub(crate) type GenerateMacroTokens = fn(tele_ty: &Telety) -> Option<TokenStream>;
+const MULTI_KEYWORD: &str = "multi";
+const EXTENSION_KEYWORD: &str = "ext";
+/// The newest version whose [Command]s accept and ignore any arguments
+const IGNORED_ARGUMENTS_VERSION: usize = 1;
+/// The argument of the [v0::PATH] command which asks for the supported versions instead
+const DOWNGRADE_PROBE: &str = "versions";
+
+/// The item names with a proxy in `telety::std`, and the module containing each
+#[cfg(feature = "std-proxies")]
+const STD_PROXIES: &[(&str, &str)] = &[
+    ("Option", "option"),
+    ("Result", "result"),
+    ("Vec", "vec"),
+    ("Box", "boxed"),
+    ("String", "string"),
+    ("Cow", "borrow"),
+    ("HashMap", "collections"),
+    ("HashSet", "collections"),
+    ("BTreeMap", "collections"),
+    ("BTreeSet", "collections"),
+    ("Rc", "rc"),
+    ("Arc", "sync"),
+    ("Clone", "clone"),
+    ("Copy", "marker"),
+    ("Default", "default"),
+    ("Debug", "fmt"),
+    ("Display", "fmt"),
+    ("PartialEq", "cmp"),
+    ("Eq", "cmp"),
+    ("PartialOrd", "cmp"),
+    ("Ord", "cmp"),
+    ("Hash", "hash"),
+];
+
+pub(crate) type GenerateReplacement = fn(tele_ty: &Telety) -> Option<TokenStream>;
+pub(crate) type GenerateArgumentsReplacement =
+    fn(tele_ty: &Telety, args: TokenStream) -> syn::Result<TokenStream>;
+
+#[derive(Clone, Copy)]
+enum Namespace {
+    Version(usize),
+    Extension(&'static str),
+}
 
 /// Used to invoke the telety-generated macro in a manageable way.
 pub struct Command {
-    version: usize,
+    namespace: Namespace,
     keyword: &'static str,
-    generate_macro_tokens: GenerateMacroTokens,
+    generate_replacement: GenerateReplacement,
+    generate_arguments_replacement: Option<GenerateArgumentsReplacement>,
 }
 
 impl Command {
     pub(crate) const fn new(
         version: usize,
         keyword: &'static str,
-        generate_macro_tokens: GenerateMacroTokens,
+        generate_replacement: GenerateReplacement,
     ) -> Self {
         Self {
-            version,
+            namespace: Namespace::Version(version),
             keyword,
-            generate_macro_tokens,
+            generate_replacement,
+            generate_arguments_replacement: None,
         }
     }
 
-    pub(crate) const fn version(&self) -> usize {
-        self.version
+    /// Create a [Command] provided by a third-party extension.  
+    /// `extension` is the name of the extension macro (the last segment of the path given to the
+    /// `extensions` argument of [`#[telety]`](https://docs.rs/telety/latest/telety/attr.telety.html)).
+    /// The extension macro receives the item definition along with the `keyword`, arguments, needle and haystack,
+    /// which can be parsed with [extension::Invocation](crate::extension::Invocation).
+    /// ## Example
+    /// ```rust,ignore
+    /// // In the extension crate
+    /// pub const FIELD_TABLE: Command = Command::extension("my_ext", "field_table");
+    /// ```
+    /// ```rust,ignore
+    /// // The item opts into the extension
+    /// #[telety(crate, extensions = "::my_ext_crate::my_ext")]
+    /// pub struct MyStruct { ... }
+    /// ```
+    pub const fn extension(extension: &'static str, keyword: &'static str) -> Self {
+        Self {
+            namespace: Namespace::Extension(extension),
+            keyword,
+            generate_replacement: |_ty| None,
+            generate_arguments_replacement: None,
+        }
+    }
+
+    /// Allow the command to be invoked with arguments. Because the arguments are not known
+    /// until the telety-generated macro is invoked, `generate_arguments_replacement` is run
+    /// by `apply_arguments` with a [Telety] recreated from the item definition.
+    #[cfg_attr(not(feature = "v2"), allow(dead_code))]
+    pub(crate) const fn with_arguments(
+        mut self,
+        generate_arguments_replacement: GenerateArgumentsReplacement,
+    ) -> Self {
+        self.generate_arguments_replacement = Some(generate_arguments_replacement);
+        self
+    }
+
+    /// Find the [Command] with the given version and keyword
+    #[doc(hidden)]
+    pub fn find(version: usize, keyword: &str) -> Option<&'static Command> {
+        version::VERSIONS
+            .iter()
+            .filter(|(v, _)| *v == version)
+            .flat_map(|(_, commands)| commands.iter())
+            .find(|command| command.keyword == keyword)
+    }
+
+    fn is_extension(&self) -> bool {
+        matches!(self.namespace, Namespace::Extension(_))
     }
 
-    fn version_lit(&self, span: Option<Span>) -> LitInt {
+    #[doc(hidden)]
+    pub fn generate_arguments_replacement(
+        &self,
+        ty: &Telety,
+        args: TokenStream,
+    ) -> syn::Result<TokenStream> {
+        if let Some(generate_arguments_replacement) = self.generate_arguments_replacement {
+            generate_arguments_replacement(ty, args)
+        } else {
+            Err(syn::Error::new(
+                args.span(),
+                format!("Command '{}' does not accept arguments", self.keyword),
+            ))
+        }
+    }
+
+    fn version_lit(&self, span: Option<Span>) -> syn::Result<LitInt> {
         let span = span.unwrap_or(Span::call_site());
-        LitInt::new(&self.version().to_string(), span)
+        match self.namespace {
+            Namespace::Version(version) => Ok(LitInt::new(&version.to_string(), span)),
+            Namespace::Extension(extension) => Err(syn::Error::new(
+                span,
+                format!(
+                    "Command '{}' of extension '{extension}' does not have a version",
+                    self.keyword
+                ),
+            )),
+        }
+    }
+
+    /// The tokens preceding the arguments in an invocation of the telety-generated macro
+    pub(crate) fn prefix(&self, span: Span) -> TokenStream {
+        let keyword = self.keyword(Some(span));
+        match self.namespace {
+            Namespace::Version(version) => {
+                let version = LitInt::new(&version.to_string(), span);
+                quote_spanned!(span => #version, #keyword)
+            }
+            Namespace::Extension(extension) => {
+                let ext = Ident::new(EXTENSION_KEYWORD, span);
+                let extension = Ident::new(extension, span);
+                quote_spanned!(span => #ext, #extension, #keyword)
+            }
+        }
     }
 
     fn keyword(&self, span: Option<Span>) -> Ident {
@@ -44,31 +180,252 @@ impl Command {
 
     #[doc(hidden)]
     pub fn generate_macro_arm(&self, ty: &Telety) -> syn::Result<Option<TokenStream>> {
-        if let Some(implementation) = (self.generate_macro_tokens)(ty) {
-            let span = ty.item().span();
+        let Some(replacement) = (self.generate_replacement)(ty) else {
+            return Ok(None);
+        };
+
+        let span = ty.item().span();
+
+        let ParameterIdents {
+            args,
+            needle,
+            haystack,
+        } = ParameterIdents::new(span);
+
+        let keyword = self.keyword(Some(span));
+        let version = self.version_lit(Some(span))?;
+        let telety_path = ty.options().telety_path();
 
-            let ParameterIdents {
-                args,
-                needle,
-                haystack,
-            } = ParameterIdents::new(span);
+        // Commands which existed before arguments were supported ignore them, as they always have.
+        // Arguments of newer commands are handled by the arm of [Command::generate_arguments_macro_arm].
+        let ignored_args = matches!(self.namespace, Namespace::Version(version) if version <= IGNORED_ARGUMENTS_VERSION)
+            .then(|| quote!($( ( $($#args:tt)* ) )?));
+
+        Ok(Some(quote_spanned! { span =>
+            (#version, #keyword #ignored_args, $#needle:tt, $($#haystack:tt)*) => {
+                #telety_path::__private::find_and_replace! {
+                    $#needle,
+                    [#replacement],
+                    $($#haystack)*
+                }
+            };
+        }))
+    }
+
+    /// Generates the macro arm which applies any [Command] invoked with arguments.
+    /// The definition of the item is passed to `apply_arguments`, which finds the [Command] and generates
+    /// its replacement, so the definition is only included in the macro once for every such [Command].  
+    /// [None] if no [Command] accepts arguments.
+    #[doc(hidden)]
+    pub fn generate_arguments_macro_arm(ty: &Telety) -> Option<TokenStream> {
+        let accepts_arguments = version::VERSIONS
+            .iter()
+            .flat_map(|(_version, commands)| commands.iter())
+            .any(|command| command.generate_arguments_replacement.is_some());
+        if !accepts_arguments {
+            return None;
+        }
 
+        let span = ty.item().span();
+
+        let ParameterIdents {
+            args,
+            needle,
+            haystack,
+        } = ParameterIdents::new(span);
+        let version = Ident::new("version", span);
+        let keyword = Ident::new("keyword", span);
+
+        let telety_path = ty.options().telety_path();
+        let definition = ty.fingerprinted_definition(false);
+
+        let apply_arguments = quote!(#telety_path::__private::apply_arguments);
+        let invocation = if ty.cfg_predicates().is_empty() {
+            quote_spanned! { span =>
+                #apply_arguments! {
+                    $#version,
+                    $#keyword,
+                    [#definition],
+                    ( $($#args)* ),
+                    $#needle,
+                    $($#haystack)*
+                }
+            }
+        } else {
+            // Evaluate each cfg predicate in the item's crate, passing the accumulated values
+            // through each macro in turn, and finally to `apply_arguments`
+            let cfg_macros: Vec<_> = (0..ty.cfg_predicates().len())
+                .map(|index| ty.cfg_macro_path(index))
+                .collect();
+            let first = &cfg_macros[0];
+            let rest = &cfg_macros[1..];
+            quote_spanned! { span =>
+                #first! {
+                    []
+                    #([#rest])*
+                    [#apply_arguments]
+                    $#version,
+                    $#keyword,
+                    [#definition],
+                    ( $($#args)* ),
+                    $#needle,
+                    $($#haystack)*
+                }
+            }
+        };
+
+        Some(quote_spanned! { span =>
+            ($#version:literal, $#keyword:ident ( $($#args:tt)* ), $#needle:tt, $($#haystack:tt)*) => {
+                #invocation
+            };
+        })
+    }
+
+    /// Generates arms which give a clear error when the command is invoked incorrectly
+    #[doc(hidden)]
+    pub fn generate_error_macro_arms(&self, ty: &Telety) -> syn::Result<TokenStream> {
+        let span = ty.item().span();
+
+        let mut arms = TokenStream::new();
+        if self.generate_arguments_replacement.is_some()
+            && (self.generate_replacement)(ty).is_none()
+        {
             let keyword = self.keyword(Some(span));
-            let version = self.version_lit(Some(span));
-            Ok(Some(quote_spanned! { span =>
-                (#version, #keyword $( ( $($#args:tt)* ) )?, $#needle:tt, $($#haystack:tt)*) => {
-                    #implementation
+            let version = self.version_lit(Some(span))?;
+            let message = format!("Command '{}' requires arguments", self.keyword);
+            arms.append_all(quote_spanned! { span =>
+                (#version, #keyword, $($rest:tt)*) => {
+                    compile_error!(#message);
                 };
-            }))
-        } else {
-            Ok(None)
+            });
+        }
+
+        Ok(arms)
+    }
+
+    /// Generates the macro arm which applies several [Command]s in a single expansion.
+    /// Because the requested commands are not known until the macro is invoked, the replacements
+    /// for every available [Command] are embedded in the arm, and the requested ones are selected
+    /// by `find_and_replace_multi`, which replaces all of their needles in one pass over the haystack.
+    #[doc(hidden)]
+    pub fn generate_multi_macro_arm(ty: &Telety) -> syn::Result<TokenStream> {
+        let span = ty.item().span();
+
+        let ParameterIdents {
+            needle, haystack, ..
+        } = ParameterIdents::new(span);
+        let version = Ident::new("version", span);
+        let keyword = Ident::new("keyword", span);
+
+        let mut table = TokenStream::new();
+        for &(_version, commands) in version::VERSIONS {
+            for command in commands {
+                if let Some(replacement) = (command.generate_replacement)(ty) {
+                    let version = command.version_lit(Some(span))?;
+                    let keyword = command.keyword(Some(span));
+                    table.append_all(quote_spanned! { span =>
+                        (#version, #keyword, [#replacement])
+                    });
+                }
+            }
+        }
+
+        let multi_version = LitInt::new(&v0::VERSION.to_string(), span);
+        let multi_keyword = Ident::new(MULTI_KEYWORD, span);
+        let telety_path = ty.options().telety_path();
+
+        Ok(quote_spanned! { span =>
+            (#multi_version, #multi_keyword, [$( ($#version:tt, $#keyword:ident, $#needle:tt) ),* $(,)?], $($#haystack:tt)*) => {
+                #telety_path::__private::find_and_replace_multi! {
+                    [#table],
+                    [$( ($#version, $#keyword, $#needle) )*],
+                    $($#haystack)*
+                }
+            };
+        })
+    }
+
+    /// Generates the macro arm answering the version probe of [Apply::with_downgrade] with the
+    /// replacement of [v0::VERSIONS].  
+    /// The probe is the [v0::PATH] command with a `versions` argument. Older telety versions have no
+    /// [v0::VERSIONS] command, but ignore the arguments of [v0::PATH] and reply with the path of the item instead.
+    #[doc(hidden)]
+    pub fn generate_downgrade_macro_arm(ty: &Telety) -> TokenStream {
+        let span = ty.item().span();
+
+        let ParameterIdents {
+            needle, haystack, ..
+        } = ParameterIdents::new(span);
+        let prefix = v0::PATH.prefix(span);
+        let versions = Ident::new(DOWNGRADE_PROBE, span);
+        let replacement = (v0::VERSIONS.generate_replacement)(ty);
+        let telety_path = ty.options().telety_path();
+
+        quote_spanned! { span =>
+            (#prefix (#versions), $#needle:tt, $($#haystack:tt)*) => {
+                #telety_path::__private::find_and_replace! {
+                    $#needle,
+                    [#replacement],
+                    $($#haystack)*
+                }
+            };
+        }
+    }
+
+    /// Generates the macro arms which forward invocations of extension [Command]s
+    /// (see [Command::extension]) to the extension macros listed in the `extensions` argument.
+    #[doc(hidden)]
+    pub fn generate_extension_macro_arms(ty: &Telety) -> syn::Result<TokenStream> {
+        let span = ty.item().span();
+
+        let ext = Ident::new(EXTENSION_KEYWORD, span);
+        let definition = ty.fingerprinted_definition(false);
+
+        let mut names: Vec<&Ident> = vec![];
+        let mut arms = TokenStream::new();
+        for extension in &ty.options().extensions {
+            let Some(last) = extension.segments.last() else {
+                return Err(syn::Error::new(extension.span(), "Expected a path"));
+            };
+            let name = &last.ident;
+            if names.contains(&name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("Extension '{name}' was already provided"),
+                ));
+            }
+            names.push(name);
+
+            // `crate` must refer to the crate defining the item, not the crate invoking the macro
+            let extension_path = if extension.leading_colon.is_none()
+                && extension
+                    .segments
+                    .first()
+                    .is_some_and(|s| s.ident == "crate")
+            {
+                let rest = extension.segments.iter().skip(1);
+                quote_spanned!(span => $crate #(:: #rest)*)
+            } else {
+                extension.to_token_stream()
+            };
+
+            arms.append_all(quote_spanned! { span =>
+                (#ext, #name, $($tokens:tt)*) => {
+                    #extension_path! {
+                        [#definition],
+                        $($tokens)*
+                    }
+                };
+            });
         }
+
+        Ok(arms)
     }
 
     /// Creates a macro invocation to use this command with the telety-generated macro at `macro_path`.  
     /// The output of the command will be inserted into `haystack` at each instance of `needle`.
     /// `macro_path` must point to a valid telety-generated macro, otherwise a compile error will occur.  
-    /// To support future [Command]s, `args` are passed to the command invocation, but they are not currently used.  
+    /// Arguments can be passed to commands which accept them with [Apply::with_arguments].  
     /// ## Example
     /// ```rust,ignore
     /// # use syn::parse2;
@@ -126,14 +483,53 @@ impl ParameterIdents {
 /// Creates the [TokenStream] for the [Command] using the given arguments.  
 /// Can be interpolated directly in a [quote!] macro.
 pub struct Apply {
-    command: &'static Command,
+    commands: Vec<(&'static Command, SingleToken)>,
     macro_path: Path,
-    needle: SingleToken,
     haystack: TokenStream,
     args: Option<TokenStream>,
-    fallback: Option<TokenStream>,
+    fallback: Option<Fallback>,
+    fallback_chain: Vec<Path>,
+    #[cfg(feature = "std-proxies")]
+    /// The path of the `std` item used to find a proxy, see [Apply::with_std_proxy_fallback]
+    std_proxy_fallback: Option<Path>,
     telety_path: Option<Path>,
-    unique_macro_ident: Option<Ident>,
+    macro_forwarding: Option<MacroForwarding>,
+    downgrade: bool,
+    /// The `then` and `otherwise` tokens of [Apply::when_telety]
+    when_telety: Option<(TokenStream, TokenStream)>,
+    position: Position,
+}
+
+/// The identifier of the exported macro used by [Apply::with_macro_forwarding]
+enum MacroForwarding {
+    Manual(Ident),
+    Auto,
+}
+
+/// What an [Apply] expands to if there is no telety-generated macro
+enum Fallback {
+    Tokens(TokenStream),
+    /// See [Apply::with_diagnostic_fallback]
+    Diagnostic,
+}
+
+/// Where the output of an [Apply] is expanded
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Item,
+    Expression,
+    Type,
+}
+
+/// The needle replaced with the versions supported by an item, named after a hash of the inputs.
+/// This distinguishes the needles of separate probes, in case one is nested in the haystack of another,
+/// while keeping the output independent of other expansions.
+fn versions_needle(inputs: impl IntoIterator<Item = TokenStream>) -> Ident {
+    let mut hasher = syn_util::TokenHasher::new();
+    for input in inputs {
+        hasher.write(input);
+    }
+    format_ident!("__telety_versions_{:016x}", hasher.finish())
 }
 
 impl Apply {
@@ -144,20 +540,108 @@ impl Apply {
         haystack: TokenStream,
     ) -> Self {
         Self {
-            command,
+            commands: vec![(command, needle)],
             macro_path,
-            needle,
             haystack,
             args: None,
             fallback: None,
+            fallback_chain: Vec::new(),
+            #[cfg(feature = "std-proxies")]
+            std_proxy_fallback: None,
             telety_path: None,
-            unique_macro_ident: None,
+            macro_forwarding: None,
+            downgrade: false,
+            when_telety: None,
+            position: Position::Item,
         }
     }
 
-    #[doc(hidden)]
+    /// Creates a macro invocation which applies several [Command]s to the telety-generated macro at `macro_path`
+    /// in a single expansion.  
+    /// The output of each command will be inserted into `haystack` at each instance of its paired needle.
+    /// This avoids nesting one [Command::apply] per command, each of which would copy the `haystack`.  
+    /// Arguments (see [Apply::with_arguments]) cannot be passed to the commands.
+    /// ## Example
+    /// ```rust,ignore
+    /// let path_needle: TokenTree = format_ident!("__path_needle__").into();
+    /// let ty_needle: TokenTree = format_ident!("__ty_needle__").into();
+    /// Apply::multi(
+    ///     parse_quote!(crate::MyTeletyObj),
+    ///     [(&v0::PATH, path_needle.clone()), (&v1::TY, ty_needle.clone())],
+    ///     quote! {
+    ///         my_crate::my_macro_implementation!(#path_needle, #ty_needle);
+    ///     },
+    /// )
+    /// ```
+    pub fn multi<N: Into<SingleToken>>(
+        macro_path: Path,
+        commands: impl IntoIterator<Item = (&'static Command, N)>,
+        haystack: impl ToTokens,
+    ) -> Self {
+        let commands = commands
+            .into_iter()
+            .map(|(command, needle)| (command, needle.into()))
+            .collect();
+
+        Self {
+            commands,
+            macro_path,
+            haystack: haystack.into_token_stream(),
+            args: None,
+            fallback: None,
+            fallback_chain: Vec::new(),
+            #[cfg(feature = "std-proxies")]
+            std_proxy_fallback: None,
+            telety_path: None,
+            macro_forwarding: None,
+            downgrade: false,
+            when_telety: None,
+            position: Position::Item,
+        }
+    }
+
+    /// Creates a macro invocation which expands to `then` if `macro_path` is a telety-generated macro,
+    /// or `otherwise` if it is not.  
+    /// The macro is identified by the versions it reports (see [v0::VERSIONS]), not only by its existence,
+    /// so an unrelated macro at `macro_path` which expands without using its input expands to neither.
+    /// An unrelated macro which does not accept the input is still a compile error.  
+    /// `otherwise` is used as the fallback, so the limitations of [Apply::with_fallback] apply.
+    /// ## Example
+    /// ```rust,ignore
+    /// Apply::when_telety(
+    ///     parse_quote!(crate::MaybeTelety),
+    ///     quote!(impl HasTelety for MyType {}),
+    ///     quote!(),
+    /// )
+    /// ```
+    pub fn when_telety(macro_path: Path, then: impl ToTokens, otherwise: impl ToTokens) -> Self {
+        let then = then.into_token_stream();
+        let otherwise = otherwise.into_token_stream();
+        let needle = versions_needle([
+            macro_path.to_token_stream(),
+            then.clone(),
+            otherwise.clone(),
+        ]);
+
+        let mut apply = Self::new(
+            &v0::VERSIONS,
+            macro_path,
+            needle.into(),
+            quote!({ #then } { #otherwise }),
+        )
+        .with_fallback(&otherwise);
+        apply.when_telety = Some((then, otherwise));
+        apply
+    }
+
     /// Pass arguments to the command invocation.  
-    /// Note: no commands currently use any arguments
+    /// Arguments are a comma-separated list, and each [Command] documents the arguments it accepts.
+    /// Invalid arguments are reported as compile errors by the telety-generated macro.
+    /// ## Example
+    /// ```rust,ignore
+    /// v2::TY.apply(macro_path, needle, haystack)
+    ///     .with_arguments(quote!(generics = [u8, String]))
+    /// ```
     pub fn with_arguments(mut self, arguments: impl ToTokens) -> Self {
         self.args.replace(arguments.into_token_stream());
         self
@@ -168,26 +652,248 @@ impl Apply {
     /// so any items cannot not be referenced from outside. Use [Apply::with_macro_forwarding]
     /// to expand the output directly in this scope.
     pub fn with_fallback(mut self, fallback: impl ToTokens) -> Self {
-        self.fallback.replace(fallback.into_token_stream());
+        self.fallback
+            .replace(Fallback::Tokens(fallback.into_token_stream()));
+        self
+    }
+
+    /// If `macro_path` does not contain a macro, instead expand to a compile error at the span of `macro_path`.  
+    /// The error names the path and suggests how to provide telety information for the item.
+    /// This replaces any fallback set by [Apply::with_fallback].
+    /// ## Example
+    /// ```rust,ignore
+    /// // error: `my_crate::MyType` does not have a telety macro
+    /// v1::TY.apply(parse_quote!(my_crate::MyType), needle, haystack)
+    ///     .with_diagnostic_fallback()
+    /// ```
+    pub fn with_diagnostic_fallback(mut self) -> Self {
+        self.fallback.replace(Fallback::Diagnostic);
+        self
+    }
+
+    /// The error when neither `macro_path` nor the fallback chain has a telety macro
+    fn missing_macro_message(&self) -> String {
+        let display = |path: &Path| path.to_token_stream().to_string().replace(' ', "");
+
+        let mut message = format!(
+            "`{}` does not have a telety macro",
+            display(&self.macro_path)
+        );
+        let fallback_chain = self.fallback_chain();
+        if !fallback_chain.is_empty() {
+            let chain: Vec<_> = fallback_chain
+                .iter()
+                .map(|path| format!("`{}`", display(path)))
+                .collect();
+            message += &format!(" (also tried {})", chain.join(", "));
+        }
+        message
+    }
+
+    fn diagnostic(&self) -> TokenStream {
+        let macro_path = self
+            .macro_path
+            .to_token_stream()
+            .to_string()
+            .replace(' ', "");
+        let mut message = self.missing_macro_message();
+        message += &format!(
+            "\n\
+            help: add `#[telety(...)]` to the item, or if it is defined in another crate, \
+            define a proxy item with `#[telety(..., proxy = \"{macro_path}\")]`\n\
+            note: telety macros are not imported by `use path::to::Item::{{self}}`, \
+            and are not available through type aliases"
+        );
+
+        let span = self.macro_path.span();
+        quote_spanned! { span =>
+            ::core::compile_error! { #message }
+        }
+    }
+
+    /// If `macro_path` does not contain a macro, apply the command to the first of `paths` which does.  
+    /// If none of them contain a macro, expand to the fallback set by [Apply::with_fallback],
+    /// or a compile error if there is none.  
+    /// Only the existence of each macro is checked, so an unrelated macro in the chain is still invoked.
+    /// Like [util::try_invoke](https://docs.rs/telety/latest/telety/util/macro.try_invoke.html),
+    /// each path must exist in some namespace.  
+    /// The chain has no effect in type position.
+    /// ## Example
+    /// ```rust,ignore
+    /// v1::TY.apply(parse_quote!(crate::MyType), needle, haystack)
+    ///     .with_fallback_chain([parse_quote!(crate::proxies::MyType)])
+    ///     .with_fallback(quote!(compile_error!("MyType has no telety information");))
+    /// ```
+    pub fn with_fallback_chain(mut self, paths: impl IntoIterator<Item = Path>) -> Self {
+        self.fallback_chain.extend(paths);
         self
     }
 
+    /// If `macro_path` does not contain a macro and is the path of an item with a proxy
+    /// in [telety::std](https://docs.rs/telety/latest/telety/std/index.html) (e.g. `::core::option::Option`
+    /// or `std::collections::HashMap`), apply the command to the proxy instead.  
+    /// The proxy is tried after any paths from [Apply::with_fallback_chain]. Only paths beginning with
+    /// `std`, `core` or `alloc` are matched, by the name of their last segment.
+    /// Single-segment paths to prelude items (e.g. `Option`) and [Alias](crate::Alias) paths do not
+    /// name the `std` item, so use [Apply::with_std_proxy_fallback_for] for those.
+    #[cfg(feature = "std-proxies")]
+    pub fn with_std_proxy_fallback(mut self) -> Self {
+        self.std_proxy_fallback = Some(self.macro_path.clone());
+        self
+    }
+
+    /// Like [Apply::with_std_proxy_fallback], but the proxy is found from `std_path`, the path of the
+    /// `std` item which `macro_path` refers to.
+    /// ## Example
+    /// ```rust,ignore
+    /// // `alias` is the alias of a field of type `Option<T>`
+    /// v1::TY.apply(alias.to_macro_path(), needle, haystack)
+    ///     .with_std_proxy_fallback_for(parse_quote!(::core::option::Option))
+    /// ```
+    #[cfg(feature = "std-proxies")]
+    pub fn with_std_proxy_fallback_for(mut self, std_path: Path) -> Self {
+        self.std_proxy_fallback = Some(std_path);
+        self
+    }
+
+    /// The paths tried if `macro_path` does not contain a macro
+    fn fallback_chain(&self) -> Cow<'_, [Path]> {
+        #[cfg(feature = "std-proxies")]
+        if let Some(std_path) = &self.std_proxy_fallback
+            && let Some(root) = std_path.segments.first()
+            && ["std", "core", "alloc"]
+                .iter()
+                .any(|name| root.ident == name)
+            && std_path.segments.len() > 1
+            && let Some(segment) = std_path.segments.last()
+            && segment.arguments.is_none()
+            && let Some((name, module)) = STD_PROXIES
+                .iter()
+                .find(|(name, _module)| segment.ident == name)
+        {
+            let telety_path = self.telety_path();
+            let span = segment.ident.span();
+            let module = Ident::new(module, span);
+            let name = Ident::new(name, span);
+
+            let mut fallback_chain = self.fallback_chain.clone();
+            fallback_chain.push(parse_quote_spanned!(span => #telety_path::std::#module::#name));
+            return Cow::Owned(fallback_chain);
+        }
+
+        Cow::Borrowed(&self.fallback_chain)
+    }
+
+    fn telety_path(&self) -> Cow<'_, Path> {
+        self.telety_path
+            .as_ref()
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| Cow::Owned(manifest::telety_path()))
+    }
+
     /// Specify the location of the telety crate.  
-    /// This is only required if telety is not located at the default path `::telety`
-    /// and [Apply::with_fallback] is used.
+    /// This is only required if telety is not located at the default path
+    /// (`::telety`, or its renamed path in the calling crate's `Cargo.toml`) and [Apply::with_fallback] is used.
     pub fn with_telety_path(mut self, telety_path: Path) -> Self {
         self.telety_path.replace(telety_path);
         self
     }
 
+    /// If the item does not support the version of the [Command], use the same command from the closest
+    /// older version the item does support instead.  
+    /// If no supported version has the command, expand to the fallback set by [Apply::with_fallback],
+    /// or a compile error if there is none.  
+    /// Items compiled with a telety version without [v0::VERSIONS] are treated as supporting versions 0 and 1.
+    /// Downgrade is only supported when applying a single versioned [Command].
+    pub fn with_downgrade(mut self) -> Self {
+        self.downgrade = true;
+        self
+    }
+
     /// If a fallback is set, forward the final haystack/fallback tokens through a macro
     /// so that they are evaluated without additional block scopes.  
     /// This is usually required if you a creating a named item (such as a `struct` or `enum`), but
     /// not for `impls`.
-    /// `unique_macro_ident` must be unique within the crate.  
+    /// `unique_macro_ident` must be unique within the crate. Use [Apply::with_auto_macro_forwarding]
+    /// to generate one instead.  
     /// This has no effect if [Apply::with_fallback] is not used.
     pub fn with_macro_forwarding(mut self, unique_macro_ident: Ident) -> Self {
-        self.unique_macro_ident.replace(unique_macro_ident);
+        self.macro_forwarding
+            .replace(MacroForwarding::Manual(unique_macro_ident));
+        self
+    }
+
+    /// Like [Apply::with_macro_forwarding], but the unique identifier is generated from the `macro_path`,
+    /// the [Command]s and a hash of the needles, arguments, haystack and fallback, along with the source
+    /// locations of the proc macro invocation and of `macro_path`.  
+    /// The locations distinguish identical applications in different places, so the identifier is
+    /// unique within the crate while staying the same across builds.
+    pub fn with_auto_macro_forwarding(mut self) -> Self {
+        self.macro_forwarding.replace(MacroForwarding::Auto);
+        self
+    }
+
+    fn auto_macro_ident(&self) -> Ident {
+        let mut hasher = syn_util::TokenHasher::new();
+        hasher.write(self.macro_path.to_token_stream());
+        for (command, needle) in &self.commands {
+            let keyword = command.keyword(None);
+            hasher.write(quote!(#keyword #needle));
+        }
+        if let Some(args) = &self.args {
+            hasher.write(args.clone());
+        }
+        hasher.write(self.haystack.clone());
+        match &self.fallback {
+            Some(Fallback::Tokens(fallback)) => hasher.write(fallback.clone()),
+            Some(Fallback::Diagnostic) => hasher.write(self.diagnostic()),
+            None => {}
+        }
+        // `macro_path` keeps its own location if the invocation is generated by a `macro_rules!` macro
+        hasher.write_location(Span::call_site());
+        hasher.write_location(self.macro_path.span());
+        let hash = hasher.finish();
+
+        let name = self
+            .macro_path
+            .segments
+            .last()
+            .map(|segment| segment.ident.unraw());
+        let keyword = match self.commands.as_slice() {
+            [(command, _needle)] => command.keyword(None),
+            _ => format_ident!("{MULTI_KEYWORD}"),
+        };
+
+        format_ident!(
+            "__telety_forward_{}_{keyword}_{hash:016x}",
+            name.unwrap_or_else(|| format_ident!("macro"))
+        )
+    }
+
+    /// Expand in expression position, such as inside a function body or a `const` initializer.
+    /// The haystack and the fallback must be expressions.  
+    /// [Apply::with_macro_forwarding] has no effect in expression position.
+    /// ## Example
+    /// ```rust,ignore
+    /// let count = v2::FIELDS.apply(macro_path, needle, quote!(my_crate::count!(#needle)))
+    ///     .with_fallback(quote!(0))
+    ///     .in_expression();
+    /// quote! {
+    ///     const FIELD_COUNT: usize = #count;
+    /// }
+    /// ```
+    pub fn in_expression(mut self) -> Self {
+        self.position = Position::Expression;
+        self
+    }
+
+    /// Expand in type position, such as a field type or an associated type in an `impl` block.  
+    /// Types cannot contain the output of a [Command], so this is only supported with [Apply::when_telety],
+    /// where `then` and `otherwise` must be types. The selected type is
+    /// `<telety::util::Select<{ /* is telety */ }> as telety::util::Choose<then, otherwise>>::Output`.  
+    /// [Apply::with_macro_forwarding] has no effect in type position.
+    pub fn in_type(mut self) -> Self {
+        self.position = Position::Type;
         self
     }
 }
@@ -195,25 +901,72 @@ impl Apply {
 impl ToTokens for Apply {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let macro_path = &self.macro_path;
-        let needle = &self.needle;
         let mut haystack = self.haystack.to_token_stream();
         let args = self.args.as_ref().map(|ts| quote!((#ts)));
 
         let span = self.haystack.span();
-        let version = self.command.version_lit(Some(span));
-        let keyword = self.command.keyword(Some(span));
+
+        let telety_path = self.telety_path();
+        let fallback_chain = self.fallback_chain();
+
+        if self.position == Position::Type {
+            let output = if let Some((then, otherwise)) = &self.when_telety {
+                quote_spanned! { span =>
+                    <#telety_path::util::Select<{ #telety_path::util::is_telety!(#macro_path) }>
+                        as #telety_path::util::Choose<#then, #otherwise>>::Output
+                }
+            } else {
+                syn::Error::new(
+                    span,
+                    "Type position is only supported with Apply::when_telety",
+                )
+                .into_compile_error()
+            };
+            output.to_tokens(tokens);
+            return;
+        }
+
+        if self.when_telety.is_some()
+            && let [(_command, needle)] = self.commands.as_slice()
+        {
+            haystack = quote_spanned! { span =>
+                #telety_path::__private::handshake! { #needle, #haystack }
+            };
+        }
+
+        let mut fallback = self.fallback.as_ref().map(|fallback| match fallback {
+            Fallback::Tokens(fallback) => fallback.clone(),
+            Fallback::Diagnostic => self.diagnostic(),
+        });
+        if !fallback_chain.is_empty() && fallback.is_none() {
+            let message = self.missing_macro_message();
+            let span = self.macro_path.span();
+            fallback = Some(match self.position {
+                Position::Expression => quote_spanned!(span => ::core::compile_error!(#message)),
+                Position::Item | Position::Type => {
+                    quote_spanned!(span => ::core::compile_error! { #message })
+                }
+            });
+        }
+
+        // Forwarding defines macros, which requires item position
+        let unique_macro_ident = match (&self.macro_forwarding, self.position) {
+            (Some(MacroForwarding::Manual(unique_macro_ident)), Position::Item) => {
+                Some(unique_macro_ident.clone())
+            }
+            (Some(MacroForwarding::Auto), Position::Item) if fallback.is_some() => {
+                Some(self.auto_macro_ident())
+            }
+            _ => None,
+        };
 
         let textual_macro_ident: Ident = format_ident!(
             "my_macro_{}",
-            self.unique_macro_ident
-                .as_ref()
-                .unwrap_or(&format_ident!("a"))
+            unique_macro_ident.as_ref().unwrap_or(&format_ident!("a"))
         );
 
-        let mut fallback = self.fallback.as_ref().map(|f| f.to_token_stream());
-
         if let Some(fallback) = fallback.as_mut()
-            && let Some(unique_macro_ident) = &self.unique_macro_ident
+            && let Some(unique_macro_ident) = &unique_macro_ident
         {
             let macro_wrapper = |contents: &TokenStream| {
                 // Replace `$` in the original content with `$dollar dollar`
@@ -228,6 +981,9 @@ impl ToTokens for Apply {
                     // Export a macro...
                     #[doc(hidden)]
                     #[macro_export]
+                    // Forwarded contents may be nested in another forwarding, and `crate` refers to the
+                    // calling crate, which defines the macro
+                    #[allow(non_local_definitions, clippy::crate_in_macro_def)]
                     macro_rules! #unique_macro_ident {
                         ($dollar:tt) => {
                             // which defines a macro, ...
@@ -246,25 +1002,107 @@ impl ToTokens for Apply {
             haystack = macro_wrapper(&haystack);
         }
 
-        let mut output = parse_quote_spanned! { span =>
-            #macro_path! { #version, #keyword #args, #needle, #haystack }
+        // The invocation of the telety-generated macro, which may be at `macro_path`
+        // or one of the paths of the fallback chain
+        let invocation = |macro_path: &Path| -> TokenStream {
+            if self.downgrade {
+                if let [(command, needle)] = self.commands.as_slice()
+                    && let Namespace::Version(version) = command.namespace
+                {
+                    // Commands with the same keyword, from the requested version down to version 0
+                    let candidates = (0..=version)
+                        .rev()
+                        .filter(|&version| {
+                            Command::find(version, command.keyword).is_some_and(|candidate| {
+                                args.is_none() || candidate.generate_arguments_replacement.is_some()
+                            })
+                        })
+                        .map(|version| LitInt::new(&version.to_string(), span));
+
+                    let keyword = command.keyword(Some(span));
+                    let versions_needle = versions_needle([
+                        macro_path.to_token_stream(),
+                        quote!(#keyword #args #needle),
+                        haystack.clone(),
+                    ]);
+                    let probe_prefix = v0::PATH.prefix(span);
+                    let probe = Ident::new(DOWNGRADE_PROBE, span);
+                    let fallback = fallback.as_ref().map(|fallback| quote!({ #fallback }));
+
+                    parse_quote_spanned! { span =>
+                        #macro_path! { #probe_prefix (#probe), #versions_needle,
+                            #telety_path::__private::downgrade! {
+                                #versions_needle,
+                                [#(#candidates),*],
+                                #keyword #args,
+                                #macro_path,
+                                #needle,
+                                [#fallback],
+                                #haystack
+                            }
+                        }
+                    }
+                } else {
+                    syn::Error::new(
+                        span,
+                        "Downgrade is only supported when applying a single versioned command",
+                    )
+                    .into_compile_error()
+                }
+            } else if let [(command, needle)] = self.commands.as_slice() {
+                let prefix = command.prefix(span);
+
+                parse_quote_spanned! { span =>
+                    #macro_path! { #prefix #args, #needle, #haystack }
+                }
+            } else if args.is_some() {
+                syn::Error::new(span, "Arguments cannot be passed to multiple commands")
+                    .into_compile_error()
+            } else if self
+                .commands
+                .iter()
+                .any(|(command, _)| command.is_extension())
+            {
+                syn::Error::new(
+                    span,
+                    "Extension commands cannot be combined with other commands",
+                )
+                .into_compile_error()
+            } else {
+                let version = LitInt::new(&v0::VERSION.to_string(), span);
+                let keyword = Ident::new(MULTI_KEYWORD, span);
+
+                let mut commands = TokenStream::new();
+                for (command, needle) in &self.commands {
+                    let prefix = command.prefix(span);
+                    commands.append_all(quote_spanned! { span => (#prefix, #needle), });
+                }
+
+                parse_quote_spanned! { span =>
+                    #macro_path! { #version, #keyword, [#commands], #haystack }
+                }
+            }
         };
 
+        let mut output = invocation(macro_path);
+        let chain: Vec<_> = fallback_chain.iter().map(invocation).collect();
+
         if let Some(fallback) = fallback {
-            let telety_path = self
-                .telety_path
-                .as_ref()
-                .map(Cow::Borrowed)
-                .unwrap_or_else(|| Cow::Owned(parse_quote!(::telety)));
+            let position = match self.position {
+                Position::Expression => Some(quote_spanned!(span => expr =>)),
+                Position::Item | Position::Type => None,
+            };
 
             output = parse_quote_spanned! { span =>
                 #telety_path::util::try_invoke! {
+                    #position
                     #output
+                    #(else #chain)*
                     #fallback
                 }
             };
 
-            if let Some(unique_macro_ident) = &self.unique_macro_ident {
+            if let Some(unique_macro_ident) = &unique_macro_ident {
                 let temp_ident = format_ident!("_{unique_macro_ident}");
 
                 // In order to invoke the macro from its export at the crate root, we need this trick:
@@ -276,6 +1114,7 @@ impl ToTokens for Apply {
                 let import = quote_spanned! { span =>
                     #[macro_use]
                     #[doc(hidden)]
+                    #[allow(non_snake_case)]
                     mod #temp_ident {
                         pub(super) use crate::*;
 
@@ -0,0 +1,557 @@
+//! Recursively reads the definitions of telety items which appear in another item.
+//!
+//! [Deep] reads the definition of an item, then the definition of each aliased type in the item
+//! (through [Alias::to_macro_path](crate::Alias::to_macro_path)), then each aliased type in those items,
+//! and so on, up to a maximum depth. Types without telety are included in the tree, but have no definition.
+//! An item which contains itself (directly or indirectly) is only expanded once on each branch.
+//! Once every definition has been read, the continuation macro is invoked with the tree of definitions,
+//! which can be read with [second_stage].
+//! Like [Apply::with_fallback](crate::Apply::with_fallback), reading a type which may not have telety
+//! requires an anonymous block, so the continuation is expanded inside anonymous blocks.
+//! Its output cannot be referenced by name, but trait impls (and any other side effects) are still applied.
+//! ## Example
+//! ```rust,ignore
+//! /// impl_flatten!(path_to_struct)
+//! #[proc_macro]
+//! pub fn impl_flatten(tokens: TokenStream) -> TokenStream {
+//!     let (Ok(ts) | Err(ts)) = (|| {
+//!         let root: Path = syn::parse(tokens)?;
+//!         Ok(Deep::new(parse_quote!(::my_crate::impl_flatten_impl))
+//!             .with_max_depth(2)
+//!             .expand(root, quote!()))
+//!     })()
+//!     .map_err(syn::Error::into_compile_error);
+//!     ts.into()
+//! }
+//!
+//! #[doc(hidden)]
+//! #[proc_macro]
+//! pub fn impl_flatten_impl(tokens: TokenStream) -> TokenStream {
+//!     let (Ok(ts) | Err(ts)) = telety::deep::second_stage(tokens.into(), |root, _args| {
+//!         for child in root.children().nodes() {
+//!             // ...
+//!         }
+//!         // Generate `impl Flatten for MyStruct { ... }`
+//!     })
+//!     .map_err(syn::Error::into_compile_error);
+//!     ts.into()
+//! }
+//! ```
+
+use proc_macro2::{Group, Literal, TokenStream};
+use quote::{ToTokens, format_ident, quote};
+use syn::{
+    Ident, Item, LitInt, Path, Token, braced, bracketed, parenthesized,
+    parse::{Parse, ParseStream, Parser as _},
+    parse2,
+};
+
+use crate::{Telety, alias, manifest, version::v1};
+
+/// The maximum depth used if [Deep::with_max_depth] is not called
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Generates the invocations which read an item and the telety items it contains.
+pub struct Deep {
+    continuation: Path,
+    max_depth: usize,
+    telety_path: Option<Path>,
+}
+
+impl Deep {
+    /// `continuation` is the path to the macro which receives the tree of definitions,
+    /// such as a proc macro using [second_stage].
+    /// The path must be valid wherever the output of [Deep::expand] is placed (e.g. `::my_crate::my_macro_impl`).
+    pub fn new(continuation: Path) -> Self {
+        Self {
+            continuation,
+            max_depth: DEFAULT_MAX_DEPTH,
+            telety_path: None,
+        }
+    }
+
+    /// The number of levels of aliased types to read below the root item.
+    /// With a `max_depth` of 0, only the root item is read.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Specify the location of the telety crate.
+    /// See [Apply::with_telety_path](crate::Apply::with_telety_path).
+    pub fn with_telety_path(mut self, telety_path: Path) -> Self {
+        self.telety_path.replace(telety_path);
+        self
+    }
+
+    /// Read the telety item at `root` and the items it contains, then invoke the continuation
+    /// with the tree of definitions, followed by `args`.
+    /// `root` must point to a telety item, otherwise a compile error will occur.
+    pub fn expand(self, root: Path, args: impl ToTokens) -> TokenStream {
+        let root = Entry {
+            parent: None,
+            depth: 0,
+            path: root,
+            status: Status::Pending,
+        };
+        self.start(root, args.into_token_stream())
+    }
+
+    /// Read the telety items contained in `telety`, then invoke the continuation
+    /// with the tree of definitions (with `telety` at the root), followed by `args`.
+    pub fn expand_telety(self, telety: &Telety, args: impl ToTokens) -> TokenStream {
+        let root = Entry {
+            parent: None,
+            depth: 0,
+            path: telety.path(),
+            status: Status::Fetched(telety.definition()),
+        };
+        self.start(root, args.into_token_stream())
+    }
+
+    fn start(self, root: Entry, args: TokenStream) -> TokenStream {
+        let Self {
+            continuation,
+            max_depth,
+            telety_path,
+        } = self;
+
+        State {
+            config: Config {
+                telety_path: telety_path.unwrap_or_else(manifest::telety_path),
+                continuation,
+                max_depth,
+            },
+            entries: vec![root],
+            args,
+        }
+        .step()
+        .unwrap_or_else(syn::Error::into_compile_error)
+    }
+}
+
+/// `[(telety_path) (continuation) max_depth]`
+struct Config {
+    telety_path: Path,
+    continuation: Path,
+    max_depth: usize,
+}
+
+impl Parse for Config {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        bracketed!(content in input);
+
+        let telety_path;
+        parenthesized!(telety_path in content);
+        let continuation;
+        parenthesized!(continuation in content);
+        let max_depth: LitInt = content.parse()?;
+
+        Ok(Self {
+            telety_path: telety_path.parse()?,
+            continuation: continuation.parse()?,
+            max_depth: max_depth.base10_parse()?,
+        })
+    }
+}
+
+impl ToTokens for Config {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            telety_path,
+            continuation,
+            max_depth,
+        } = self;
+
+        let max_depth = Literal::usize_unsuffixed(*max_depth);
+
+        quote!([(#telety_path) (#continuation) #max_depth]).to_tokens(tokens);
+    }
+}
+
+/// `{ parent depth (path) status }`, where `parent` is `_` for the root
+struct Entry {
+    parent: Option<usize>,
+    depth: usize,
+    path: Path,
+    status: Status,
+}
+
+enum Status {
+    /// `pending` - The definition has not been read
+    Pending,
+    /// `[]` - The type does not have telety
+    NotTelety,
+    /// `[definition] new` - The definition has been read, but its aliased types have not been added
+    Fetched(TokenStream),
+    /// `[definition] expanded unique_ident` - The item's aliased types have been added.
+    /// The [unique identifier](alias::Map::unique_ident) is kept to find cycles without reading the definition again.
+    Expanded(TokenStream, Ident),
+    /// `[definition] cycle` - The item is also an ancestor of this entry
+    Cycle(TokenStream),
+    /// `[definition] ..` - The entry is at the maximum depth
+    DepthLimit(TokenStream),
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        braced!(content in input);
+
+        let parent = if content.parse::<Option<Token![_]>>()?.is_some() {
+            None
+        } else {
+            Some(content.parse::<LitInt>()?.base10_parse()?)
+        };
+        let depth = content.parse::<LitInt>()?.base10_parse()?;
+        let path;
+        parenthesized!(path in content);
+        let path = path.parse()?;
+
+        let status = if content.peek(syn::token::Bracket) {
+            let definition: Group = content.parse()?;
+            let definition = definition.stream();
+            if definition.is_empty() {
+                Status::NotTelety
+            } else if content.parse::<Option<Token![..]>>()?.is_some() {
+                Status::DepthLimit(definition)
+            } else {
+                let marker: Ident = content.parse()?;
+                if marker == "new" {
+                    Status::Fetched(definition)
+                } else if marker == "expanded" {
+                    Status::Expanded(definition, content.parse()?)
+                } else if marker == "cycle" {
+                    Status::Cycle(definition)
+                } else {
+                    return Err(syn::Error::new(marker.span(), "Unexpected entry status"));
+                }
+            }
+        } else {
+            let marker: Ident = content.parse()?;
+            if marker != "pending" {
+                return Err(syn::Error::new(marker.span(), "Unexpected entry status"));
+            }
+            Status::Pending
+        };
+
+        Ok(Self {
+            parent,
+            depth,
+            path,
+            status,
+        })
+    }
+}
+
+impl ToTokens for Entry {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            parent,
+            depth,
+            path,
+            status,
+        } = self;
+
+        let depth = Literal::usize_unsuffixed(*depth);
+        let parent = match parent {
+            Some(parent) => Literal::usize_unsuffixed(*parent).into_token_stream(),
+            None => quote!(_),
+        };
+        let status = match status {
+            Status::Pending => quote!(pending),
+            Status::NotTelety => quote!([]),
+            Status::Fetched(definition) => quote!([#definition] new),
+            Status::Expanded(definition, unique_ident) => {
+                quote!([#definition] expanded #unique_ident)
+            }
+            Status::Cycle(definition) => quote!([#definition] cycle),
+            Status::DepthLimit(definition) => quote!([#definition] ..),
+        };
+
+        quote!({ #parent #depth (#path) #status }).to_tokens(tokens);
+    }
+}
+
+/// `[config] [entries] args`
+struct State {
+    config: Config,
+    entries: Vec<Entry>,
+    args: TokenStream,
+}
+
+impl Parse for State {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let config = input.parse()?;
+        let content;
+        bracketed!(content in input);
+        let mut entries = vec![];
+        while !content.is_empty() {
+            entries.push(content.parse()?);
+        }
+        let args = input.parse()?;
+
+        Ok(Self {
+            config,
+            entries,
+            args,
+        })
+    }
+}
+
+impl ToTokens for State {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            config,
+            entries,
+            args,
+        } = self;
+
+        quote!(#config [#(#entries)*] #args).to_tokens(tokens);
+    }
+}
+
+impl State {
+    /// Add the aliased types of newly read definitions, then read the next pending entry,
+    /// or invoke the continuation if all entries have been read.
+    fn step(mut self) -> syn::Result<TokenStream> {
+        for index in 0..self.entries.len() {
+            let Status::Fetched(definition) = &self.entries[index].status else {
+                continue;
+            };
+            let definition = definition.clone();
+
+            let item: Item = parse2(definition.clone())?;
+            let telety = Telety::new(&item)?;
+            let unique_ident = telety.alias_map().unique_ident().clone();
+
+            let mut is_cycle = false;
+            let mut ancestor = self.entries[index].parent;
+            while let Some(ancestor_index) = ancestor {
+                let ancestor_entry = &self.entries[ancestor_index];
+                if let Status::Expanded(_definition, ancestor_ident) = &ancestor_entry.status
+                    && *ancestor_ident == unique_ident
+                {
+                    is_cycle = true;
+                    break;
+                }
+                ancestor = ancestor_entry.parent;
+            }
+
+            let depth = self.entries[index].depth;
+            self.entries[index].status = if is_cycle {
+                Status::Cycle(definition)
+            } else if depth >= self.config.max_depth {
+                Status::DepthLimit(definition)
+            } else {
+                let mut aliases: Vec<_> = telety
+                    .alias_map()
+                    .iter_aliases()
+                    .filter_map(|alias| match (alias.index, alias.kind()) {
+                        (alias::Index::Secondary(alias_index), alias::Kind::Type) => {
+                            Some((alias_index, alias.to_macro_path()))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                aliases.sort_by_key(|(alias_index, _path)| *alias_index);
+
+                self.entries
+                    .extend(aliases.into_iter().map(|(_alias_index, path)| Entry {
+                        parent: Some(index),
+                        depth: depth + 1,
+                        path,
+                        status: Status::Pending,
+                    }));
+
+                Status::Expanded(definition, unique_ident)
+            };
+        }
+
+        let Some(pending) = self
+            .entries
+            .iter()
+            .position(|entry| matches!(entry.status, Status::Pending))
+        else {
+            let continuation = &self.config.continuation;
+            let tree = Tree {
+                entries: &self.entries,
+                index: 0,
+            };
+            let args = &self.args;
+            return Ok(quote! {
+                #continuation! { #tree #args }
+            });
+        };
+
+        let needle = format_ident!("__telety_deep_needle");
+        let telety_path = self.config.telety_path.clone();
+        let macro_path = self.entries[pending].path.clone();
+        let is_root = self.entries[pending].parent.is_none();
+
+        self.entries[pending].status = Status::Fetched(needle.to_token_stream());
+        let haystack = quote!(#telety_path::__private::deep_step! { #self });
+
+        let mut apply = v1::TY
+            .apply(macro_path, needle, haystack)
+            .with_telety_path(telety_path.clone());
+
+        // The root must be a telety item, so the missing macro error is reported for it
+        if !is_root {
+            self.entries[pending].status = Status::NotTelety;
+            let fallback = quote!(#telety_path::__private::deep_step! { #self });
+            apply = apply.with_fallback(fallback);
+        }
+
+        Ok(apply.into_token_stream())
+    }
+}
+
+/// Emits the entries as a nested tree for the continuation
+struct Tree<'a> {
+    entries: &'a [Entry],
+    index: usize,
+}
+
+impl ToTokens for Tree<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let entry = &self.entries[self.index];
+        let path = &entry.path;
+
+        let node = match &entry.status {
+            Status::NotTelety => quote!((#path) []),
+            Status::Expanded(definition, _unique_ident) => {
+                let children = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_index, child)| child.parent == Some(self.index))
+                    .map(|(index, _child)| Tree {
+                        entries: self.entries,
+                        index,
+                    });
+                quote!((#path) [#definition] { #(#children),* })
+            }
+            Status::Cycle(definition) => quote!((#path) [#definition] cycle),
+            Status::DepthLimit(definition) => quote!((#path) [#definition] ..),
+            Status::Pending | Status::Fetched(_) => {
+                unreachable!("All entries are read before invoking the continuation")
+            }
+        };
+
+        node.to_tokens(tokens);
+    }
+}
+
+#[doc(hidden)]
+pub fn step(input: TokenStream) -> syn::Result<TokenStream> {
+    parse2::<State>(input)?.step()
+}
+
+/// A node in the tree of definitions read by [Deep]
+pub struct Node {
+    path: Path,
+    definition: Option<Item>,
+    children: Children,
+}
+
+/// The contents of a [Node] below its definition
+pub enum Children {
+    /// The definitions of the aliased types in the item, in the order of their aliases
+    Expanded(Vec<Node>),
+    /// The item is also an ancestor of this [Node], so it was not expanded again
+    Cycle,
+    /// The [Node] is at the maximum depth, so its aliased types were not read
+    DepthLimit,
+    /// The type does not have telety
+    NotTelety,
+}
+
+impl Children {
+    /// The child [Node]s, which is empty unless the children are [Children::Expanded]
+    pub fn nodes(&self) -> &[Node] {
+        match self {
+            Self::Expanded(nodes) => nodes,
+            Self::Cycle | Self::DepthLimit | Self::NotTelety => &[],
+        }
+    }
+}
+
+impl Node {
+    /// The path the definition was read from. For the root, this is the path given to [Deep::expand].
+    /// For other [Node]s, this is the [Alias::to_macro_path](crate::Alias::to_macro_path) of the alias
+    /// in the parent's [alias::Map].
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The item definition, or [None] if the type does not have telety
+    pub fn item(&self) -> Option<&Item> {
+        self.definition.as_ref()
+    }
+
+    /// The [Telety] for the item, or [None] if the type does not have telety
+    pub fn telety(&self) -> Option<syn::Result<Telety<'_>>> {
+        self.definition.as_ref().map(Telety::new)
+    }
+
+    pub fn children(&self) -> &Children {
+        &self.children
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path;
+        parenthesized!(path in input);
+        let path = path.parse()?;
+
+        let definition: Group = input.parse()?;
+        if definition.stream().is_empty() {
+            return Ok(Self {
+                path,
+                definition: None,
+                children: Children::NotTelety,
+            });
+        }
+        let definition = Some(parse2(definition.stream())?);
+
+        let children = if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            let nodes = content.parse_terminated(Node::parse, Token![,])?;
+            Children::Expanded(nodes.into_iter().collect())
+        } else if input.parse::<Option<Token![..]>>()?.is_some() {
+            Children::DepthLimit
+        } else {
+            let marker: Ident = input.parse()?;
+            if marker != "cycle" {
+                return Err(syn::Error::new(marker.span(), "Expected 'cycle'"));
+            }
+            Children::Cycle
+        };
+
+        Ok(Self {
+            path,
+            definition,
+            children,
+        })
+    }
+}
+
+/// Implements a continuation for [Deep], invoked once every definition has been read.
+/// `f` is called with the root [Node] and the `args` given to [Deep::expand].
+pub fn second_stage(
+    input: TokenStream,
+    f: impl FnOnce(&Node, TokenStream) -> syn::Result<TokenStream>,
+) -> syn::Result<TokenStream> {
+    let (root, args) = (|input: ParseStream| {
+        let root: Node = input.parse()?;
+        let args: TokenStream = input.parse()?;
+        Ok((root, args))
+    })
+    .parse2(input)?;
+
+    f(&root, args)
+}
@@ -0,0 +1,159 @@
+//! Helpers for writing a telety proc macro as a single function.
+//!
+//! Reading telety information takes two proc macros: a first stage which invokes the telety-generated macros,
+//! and a second stage which receives the item definitions. [FirstStage] and [second_stage] implement both,
+//! so the proc macro author only needs to handle the [Telety] objects.
+//! The input to the first stage is a comma-separated list of paths to telety items, optionally followed by `;`
+//! and any other tokens, which are forwarded to the second stage unchanged.
+//! ## Example
+//! ```rust,ignore
+//! /// mix!(path_to_struct0, path_to_struct1; new_struct_ident)
+//! #[proc_macro]
+//! pub fn mix(tokens: TokenStream) -> TokenStream {
+//!     let (Ok(ts) | Err(ts)) = FirstStage::new(parse_quote!(::my_crate::mix_impl))
+//!         .expand(tokens.into())
+//!         .map_err(syn::Error::into_compile_error);
+//!     ts.into()
+//! }
+//!
+//! #[doc(hidden)]
+//! #[proc_macro]
+//! pub fn mix_impl(tokens: TokenStream) -> TokenStream {
+//!     let (Ok(ts) | Err(ts)) = telety::driver::second_stage(tokens.into(), |items, args| {
+//!         // ...
+//!     })
+//!     .map_err(syn::Error::into_compile_error);
+//!     ts.into()
+//! }
+//! ```
+//! The `#[telety::reflect]` attribute generates both proc macros from a single function.
+
+use proc_macro2::{Group, TokenStream};
+use quote::quote;
+use syn::{
+    Item, Path, Token, bracketed, parenthesized,
+    parse::{ParseStream, Parser as _},
+    parse2,
+};
+
+use crate::{Batch, Telety, version::v1};
+
+/// Generates the first stage of a telety proc macro, which invokes the telety-generated macro of each item,
+/// then invokes the second stage with the definitions.
+pub struct FirstStage {
+    second_stage: Path,
+    telety_path: Option<Path>,
+}
+
+impl FirstStage {
+    /// `second_stage` is the path to the second stage proc macro, which should use [second_stage].
+    /// The path must be valid wherever the first stage is invoked (e.g. `::my_crate::my_macro_impl`).
+    pub fn new(second_stage: Path) -> Self {
+        Self {
+            second_stage,
+            telety_path: None,
+        }
+    }
+
+    /// Specify the location of the telety crate.
+    /// See [Apply::with_telety_path](crate::Apply::with_telety_path).
+    pub fn with_telety_path(mut self, telety_path: Path) -> Self {
+        self.telety_path.replace(telety_path);
+        self
+    }
+
+    /// Generate the first stage from the input of the proc macro:
+    /// `path0, path1, ...; other tokens`
+    pub fn expand(self, input: TokenStream) -> syn::Result<TokenStream> {
+        let Self {
+            second_stage,
+            telety_path,
+        } = self;
+
+        let (paths, args) = (|input: ParseStream| {
+            let mut paths = vec![];
+            while !input.is_empty() && !input.peek(Token![;]) {
+                paths.push(input.parse::<Path>()?);
+                if input.is_empty() || input.peek(Token![;]) {
+                    break;
+                }
+                let _comma: Token![,] = input.parse()?;
+            }
+            if !input.is_empty() {
+                let _semi: Token![;] = input.parse()?;
+            }
+            let args: TokenStream = input.parse()?;
+            Ok((paths, args))
+        })
+        .parse2(input)?;
+
+        let mut batch = Batch::new();
+        for path in &paths {
+            batch = batch.add(path.clone(), &v1::TY);
+        }
+        if let Some(telety_path) = telety_path {
+            batch = batch.with_telety_path(telety_path);
+        }
+
+        Ok(batch.invoke(|needles| {
+            // The original paths are forwarded so errors can be reported at the user's spans
+            quote! {
+                #second_stage! {
+                    [#( (#paths) [#needles] ),*]
+                    #args
+                }
+            }
+        }))
+    }
+}
+
+/// Implements the second stage of a telety proc macro, invoked by the output of [FirstStage::expand].
+/// `f` is called with a [Telety] for each path given to the first stage, in order, and the other tokens
+/// following the paths.
+pub fn second_stage(
+    input: TokenStream,
+    f: impl FnOnce(&[Telety], TokenStream) -> syn::Result<TokenStream>,
+) -> syn::Result<TokenStream> {
+    let (entries, args) = (|input: ParseStream| {
+        let content;
+        bracketed!(content in input);
+        let mut entries = vec![];
+        while !content.is_empty() {
+            let path_content;
+            parenthesized!(path_content in content);
+            let path: TokenStream = path_content.parse()?;
+            let definition: Group = content.parse()?;
+            entries.push((path, definition.stream()));
+            if !content.is_empty() {
+                let _comma: Token![,] = content.parse()?;
+            }
+        }
+        let args: TokenStream = input.parse()?;
+        Ok((entries, args))
+    })
+    .parse2(input)?;
+
+    // The definitions have the spans of the items, which may be in another crate,
+    // so report errors at the path given by the user instead
+    let respan = |path: &TokenStream, error: syn::Error| {
+        syn::Error::new_spanned(
+            path,
+            format!("Failed to read telety item '{path}': {error}"),
+        )
+    };
+
+    let items = entries
+        .iter()
+        .map(|(path, definition)| {
+            parse2::<Item>(definition.clone()).map_err(|error| respan(path, error))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let teleties = entries
+        .iter()
+        .zip(&items)
+        .map(|((path, _definition), item)| Telety::new(item).map_err(|error| respan(path, error)))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    f(&teleties, args)
+}
@@ -0,0 +1,109 @@
+use syn::{Attribute, Fields, Item, TraitItem, Type, Visibility, parse_quote};
+
+/// Which parts of an item are included in its definition.
+/// Set by the `export` argument of the telety attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Export {
+    /// `export = "all"` - The whole item.
+    #[default]
+    All,
+    /// `export = "public"` - Only the public surface of the item:
+    /// * Fields which are not `pub` or `pub(crate)` are removed from structs and unions, which are marked `#[non_exhaustive]`.
+    ///   The types of removed tuple struct fields are replaced with `()`, so the remaining fields keep their indices.
+    /// * The bodies of default trait methods are replaced with `;`.
+    Public,
+}
+
+impl Export {
+    /// The item as it appears in its definition, if it differs from `item`.
+    pub(crate) fn redact(self, item: &Item) -> Option<Item> {
+        match self {
+            Self::All => None,
+            Self::Public => {
+                let mut item = item.clone();
+                match &mut item {
+                    Item::Struct(item_struct) => {
+                        redact_fields(&mut item_struct.fields, &mut item_struct.attrs);
+                    }
+                    Item::Union(item_union) => {
+                        let len = item_union.fields.named.len();
+                        item_union.fields.named = item_union
+                            .fields
+                            .named
+                            .pairs()
+                            .filter(|pair| is_exported(&pair.value().vis))
+                            .map(|pair| pair.cloned())
+                            .collect();
+                        if item_union.fields.named.len() != len {
+                            mark_non_exhaustive(&mut item_union.attrs);
+                        }
+                    }
+                    Item::Trait(item_trait) => {
+                        for trait_item in &mut item_trait.items {
+                            if let TraitItem::Fn(trait_item_fn) = trait_item
+                                && trait_item_fn.default.take().is_some()
+                            {
+                                trait_item_fn.semi_token = Some(Default::default());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                Some(item)
+            }
+        }
+    }
+}
+
+/// Remove (or, for tuple structs, replace) the fields which are not exported,
+/// and mark the struct if any were redacted.
+fn redact_fields(fields: &mut Fields, attrs: &mut Vec<Attribute>) {
+    let redacted = match fields {
+        Fields::Named(fields_named) => {
+            let len = fields_named.named.len();
+            fields_named.named = fields_named
+                .named
+                .pairs()
+                .filter(|pair| is_exported(&pair.value().vis))
+                .map(|pair| pair.cloned())
+                .collect();
+            fields_named.named.len() != len
+        }
+        Fields::Unnamed(fields_unnamed) => {
+            let mut redacted = false;
+            for field in &mut fields_unnamed.unnamed {
+                if !is_exported(&field.vis) {
+                    let unit: Type = parse_quote!(());
+                    if field.ty != unit || !field.attrs.is_empty() {
+                        field.attrs.clear();
+                        field.ty = unit;
+                        redacted = true;
+                    }
+                }
+            }
+            redacted
+        }
+        Fields::Unit => false,
+    };
+
+    if redacted {
+        mark_non_exhaustive(attrs);
+    }
+}
+
+fn is_exported(vis: &Visibility) -> bool {
+    match vis {
+        Visibility::Public(_) => true,
+        Visibility::Restricted(restricted) => restricted.path.is_ident("crate"),
+        Visibility::Inherited => false,
+    }
+}
+
+fn mark_non_exhaustive(attrs: &mut Vec<Attribute>) {
+    if !attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("non_exhaustive"))
+    {
+        attrs.push(parse_quote!(#[non_exhaustive]));
+    }
+}
@@ -0,0 +1,92 @@
+//! Support for third-party [Command]s.
+//!
+//! An item opts into an extension by listing the extension macro in the `extensions` argument:
+//! ```rust,ignore
+//! #[telety(crate, extensions = "::my_ext_crate::my_ext")]
+//! pub struct MyStruct { ... }
+//! ```
+//! Invoking a [Command] created by [Command::extension] forwards the item definition to the extension macro.
+//! The extension macro (usually a proc macro) can parse its input as an [Invocation].
+//! ## Example
+//! ```rust,ignore
+//! pub const FIELD_COUNT: Command = Command::extension("my_ext", "field_count");
+//!
+//! #[proc_macro]
+//! pub fn my_ext(tokens: TokenStream) -> TokenStream {
+//!     let invocation: Invocation = parse2(tokens)?;
+//!     let replacement = {
+//!         let telety = invocation.telety()?;
+//!         // ...
+//!     };
+//!     invocation.replace(replacement)
+//! }
+//! ```
+
+use proc_macro2::{Group, TokenStream};
+use quote::ToTokens;
+use syn::{
+    Ident, Item, Token,
+    parse::{Parse, ParseStream},
+    parse2,
+};
+
+#[cfg(doc)]
+use crate::Command;
+use crate::{Telety, find_and_replace::SingleToken};
+
+/// The input to an extension macro, invoked by a telety-generated macro.
+pub struct Invocation {
+    /// The item definition, including the `#[telety]` attribute
+    pub definition: Item,
+    /// The keyword of the invoked [Command]
+    pub keyword: Ident,
+    /// The arguments passed with [Apply::with_arguments](crate::Apply::with_arguments), if any
+    pub arguments: Option<TokenStream>,
+    /// The token to replace in `haystack`
+    pub needle: SingleToken,
+    /// The tokens containing `needle`
+    pub haystack: TokenStream,
+}
+
+impl Invocation {
+    /// Create a [Telety] for the item definition
+    pub fn telety(&self) -> syn::Result<Telety<'_>> {
+        Telety::new(&self.definition)
+    }
+
+    /// Replace each instance of `needle` in `haystack` with `replacement`
+    pub fn replace(self, replacement: impl ToTokens) -> TokenStream {
+        crate::find_and_replace::find_and_replace(
+            self.needle,
+            replacement.into_token_stream(),
+            self.haystack,
+        )
+    }
+}
+
+impl Parse for Invocation {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let definition: Group = input.parse()?;
+        let definition = parse2(definition.stream())?;
+        let _comma: Token![,] = input.parse()?;
+        let keyword = input.parse()?;
+        let arguments = if input.peek(syn::token::Paren) {
+            let arguments: Group = input.parse()?;
+            Some(arguments.stream())
+        } else {
+            None
+        };
+        let _comma: Token![,] = input.parse()?;
+        let needle = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let haystack = input.parse()?;
+
+        Ok(Self {
+            definition,
+            keyword,
+            arguments,
+            needle,
+            haystack,
+        })
+    }
+}
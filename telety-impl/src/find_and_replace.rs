@@ -73,29 +73,37 @@ pub fn find_and_replace(
     replacement: TokenStream,
     haystack: TokenStream,
 ) -> TokenStream {
-    fn far(needle: &SingleToken, replacement: &TokenStream, haystack: TokenStream) -> TokenStream {
-        let mut output = TokenStream::new();
+    let needle = needle.into();
+
+    find_and_replace_multi(&[(needle, replacement)], haystack)
+}
+
+/// Replaces each needle with its paired replacement in a single pass over `haystack`.
+/// If a token matches multiple needles, the first pair is used.
+pub fn find_and_replace_multi(
+    pairs: &[(SingleToken, TokenStream)],
+    haystack: TokenStream,
+) -> TokenStream {
+    let mut output = TokenStream::new();
 
-        for tt in haystack {
-            let tt = match tt {
-                TokenTree::Group(g) => TokenTree::Group(Group::new(
-                    g.delimiter(),
-                    far(needle, replacement, g.stream()),
-                )),
-                tt if needle == &tt => {
+    for tt in haystack {
+        let tt = match tt {
+            TokenTree::Group(g) => TokenTree::Group(Group::new(
+                g.delimiter(),
+                find_and_replace_multi(pairs, g.stream()),
+            )),
+            tt => {
+                if let Some((_needle, replacement)) = pairs.iter().find(|(needle, _)| needle == &tt)
+                {
                     output.extend(replacement.clone());
                     continue;
                 }
-                tt => tt,
-            };
-
-            output.append(tt);
-        }
+                tt
+            }
+        };
 
-        output
+        output.append(tt);
     }
 
-    let needle = needle.into();
-
-    far(&needle, &replacement, haystack)
+    output
 }
@@ -11,6 +11,9 @@ pub struct AliasMapArgs {
     pub unique_ident_comma: syn::Token![,],
     pub telety_path: Option<syn::Path>,
     pub telety_path_comma: syn::Token![,],
+    /// The `alias_traits` option of the item, if provided
+    pub alias_traits: Option<syn::LitBool>,
+    pub alias_traits_comma: syn::Token![,],
     pub self_type: Option<syn::Type>,
     pub self_type_comma: syn::Token![,],
     pub aliased_types: syn::punctuated::Punctuated<syn::Type, syn::Token![,]>,
@@ -38,11 +41,20 @@ impl AliasMapArgs {
             unique_ident_comma: Default::default(),
             telety_path,
             telety_path_comma: Default::default(),
+            alias_traits: None,
+            alias_traits_comma: Default::default(),
             self_type,
             self_type_comma: Default::default(),
             aliased_types: aliased_types.into_iter().collect(),
         }
     }
+
+    /// Record the `alias_traits` option of the item
+    pub fn with_alias_traits(mut self, alias_traits: Option<bool>) -> Self {
+        self.alias_traits =
+            alias_traits.map(|value| syn::LitBool::new(value, proc_macro2::Span::call_site()));
+        self
+    }
 }
 
 impl quote::ToTokens for AliasMapArgs {
@@ -57,6 +69,8 @@ impl quote::ToTokens for AliasMapArgs {
             unique_ident_comma,
             telety_path,
             telety_path_comma,
+            alias_traits,
+            alias_traits_comma,
             self_type,
             self_type_comma,
             aliased_types,
@@ -71,6 +85,8 @@ impl quote::ToTokens for AliasMapArgs {
         unique_ident_comma.to_tokens(tokens);
         telety_path.to_tokens(tokens);
         telety_path_comma.to_tokens(tokens);
+        alias_traits.to_tokens(tokens);
+        alias_traits_comma.to_tokens(tokens);
         self_type.to_tokens(tokens);
         self_type_comma.to_tokens(tokens);
         aliased_types.to_tokens(tokens);
@@ -93,6 +109,10 @@ impl syn::parse::Parse for AliasMapArgs {
             .then(|| input.parse())
             .transpose()?;
         let telety_path_comma = input.parse()?;
+        let alias_traits = (!input.peek(syn::Token![,]))
+            .then(|| input.parse())
+            .transpose()?;
+        let alias_traits_comma = input.parse()?;
         let self_type = (!input.peek(syn::Token![,]))
             .then(|| input.parse())
             .transpose()?;
@@ -109,6 +129,8 @@ impl syn::parse::Parse for AliasMapArgs {
             unique_ident_comma,
             telety_path,
             telety_path_comma,
+            alias_traits,
+            alias_traits_comma,
             self_type,
             self_type_comma,
             aliased_types,
@@ -3,11 +3,22 @@
 
 pub mod alias;
 pub use alias::Alias;
+mod batch;
+pub use batch::Batch;
+mod cfg;
 mod command;
+#[cfg(feature = "v1")]
+pub mod driver;
 pub use command::{Apply, Command};
+#[cfg(feature = "v1")]
+pub mod deep;
+mod export;
+pub use export::Export;
+pub mod extension;
 pub mod find_and_replace;
 pub mod interop;
 mod item_data;
+pub mod manifest;
 mod options;
 pub use options::Options;
 mod syn_util;
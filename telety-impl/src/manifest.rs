@@ -0,0 +1,579 @@
+//! The names of the calling crate and its dependencies, read from the environment and the crate's `Cargo.toml`.
+//! Dependencies may be renamed (`foo = { package = "foo-core" }`), so the name a crate is referred to by in paths
+//! is not necessarily its crate name.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::SystemTime,
+};
+
+use proc_macro2::Span;
+use syn::{Expr, ExprLit, Ident, Lit, Meta, Token, punctuated::Punctuated};
+use toml::{Table, Value};
+
+/// The name of the calling crate, as used in paths by other crates.
+/// Uses `CARGO_CRATE_NAME` if it is set, otherwise the `[lib]` or `[package]` name in the crate's `Cargo.toml`.
+/// Doctests are compiled as their own crate, which has no name other crates can refer to it by,
+/// so this is [None] in doctests.
+pub fn crate_name() -> syn::Result<Option<Ident>> {
+    if is_doctest() {
+        return Ok(None);
+    }
+
+    let name = match env::var("CARGO_CRATE_NAME") {
+        Ok(name) => name,
+        Err(_) => Manifest::calling()?
+            .and_then(|manifest| manifest.name.clone())
+            .ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    "Could not determine the calling crate name (CARGO_CRATE_NAME is not set, and no name was found in Cargo.toml)",
+                )
+            })?,
+    };
+
+    to_ident(&name).map(Some)
+}
+
+/// The path to the telety crate, as the calling crate refers to it.
+/// If telety is renamed in the crate's dependencies, this is the renamed path. Otherwise `::telety`.
+pub fn telety_path() -> syn::Path {
+    let name = Manifest::calling().ok().flatten().and_then(|manifest| {
+        manifest
+            .available_dependencies(is_build_script())
+            .into_iter()
+            .find(|dependency| dependency.crate_name() == "telety")
+            .map(|dependency| dependency.name.clone())
+    });
+
+    match name.as_deref().map(to_ident) {
+        Some(Ok(ident)) => syn::parse_quote!(::#ident),
+        _ => syn::parse_quote!(::telety),
+    }
+}
+
+/// Pairs of the crate name and the name the calling crate refers to it by, for each renamed dependency.
+/// Empty if the location of the crate's `Cargo.toml` is unknown.
+pub(crate) fn renames() -> syn::Result<Vec<(Ident, Ident)>> {
+    let Some(manifest) = Manifest::calling()? else {
+        return Ok(vec![]);
+    };
+
+    manifest
+        .available_dependencies(is_build_script())
+        .into_iter()
+        .filter(|dependency| dependency.crate_name() != dependency.name.replace('-', "_"))
+        .map(|dependency| {
+            Ok((
+                to_ident(&dependency.crate_name())?,
+                to_ident(&dependency.name)?,
+            ))
+        })
+        .collect()
+}
+
+fn to_ident(name: &str) -> syn::Result<Ident> {
+    syn::parse_str(&name.replace('-', "_")).map_err(|_| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("'{name}' is not a valid crate name"),
+        )
+    })
+}
+
+/// rustdoc sets this when compiling doctests
+fn is_doctest() -> bool {
+    env::var_os("UNSTABLE_RUSTDOC_TEST_PATH").is_some()
+}
+
+/// Build scripts can only use `[build-dependencies]`
+fn is_build_script() -> bool {
+    env::var("CARGO_CRATE_NAME").is_ok_and(|name| name == "build_script_build")
+}
+
+/// Parsed manifests by location, with their modification time when parsed
+type Cache = HashMap<PathBuf, (Option<SystemTime>, Rc<Manifest>)>;
+
+thread_local! {
+    /// Proc macros are invoked many times per crate, so avoid re-reading the manifest.
+    /// A proc macro server may expand macros for several crates, so this is keyed by the manifest location.
+    static MANIFESTS: RefCell<Cache> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Default)]
+struct Manifest {
+    /// The `[lib]` name, or the `[package]` name
+    name: Option<String>,
+    /// `[dependencies]`, including those of enabled `[target]` tables
+    dependencies: Vec<Dependency>,
+    /// `[dev-dependencies]`, including those of enabled `[target]` tables
+    dev_dependencies: Vec<Dependency>,
+    /// `[build-dependencies]`, including those of enabled `[target]` tables
+    build_dependencies: Vec<Dependency>,
+    /// Whether this is the root of a workspace
+    is_workspace: bool,
+    /// The dependencies in `[workspace.dependencies]`
+    workspace_dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Dependency {
+    /// The name used in paths
+    name: String,
+    /// The package name, if the dependency is renamed
+    package: Option<String>,
+    /// Whether the dependency is inherited from the workspace (`workspace = true`)
+    workspace: bool,
+}
+
+impl Dependency {
+    fn crate_name(&self) -> String {
+        self.package
+            .as_ref()
+            .unwrap_or(&self.name)
+            .replace('-', "_")
+    }
+}
+
+impl Manifest {
+    /// The manifest of the calling crate, or [None] if `CARGO_MANIFEST_DIR` is not set.
+    fn calling() -> syn::Result<Option<Rc<Self>>> {
+        let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") else {
+            return Ok(None);
+        };
+
+        let mut manifest = Self::load(&Path::new(&manifest_dir).join("Cargo.toml"))?;
+
+        // Fill in the package names of dependencies inherited from the workspace
+        if manifest
+            .all_dependencies()
+            .any(|dependency| dependency.workspace && dependency.package.is_none())
+            && let Some(workspace) = Self::workspace(Path::new(&manifest_dir))?
+        {
+            let manifest = Rc::make_mut(&mut manifest);
+            for dependency in manifest
+                .dependencies
+                .iter_mut()
+                .chain(&mut manifest.dev_dependencies)
+                .chain(&mut manifest.build_dependencies)
+            {
+                if dependency.workspace && dependency.package.is_none() {
+                    dependency.package = workspace
+                        .workspace_dependencies
+                        .iter()
+                        .find(|inherited| inherited.name == dependency.name)
+                        .and_then(|inherited| inherited.package.clone());
+                }
+            }
+        }
+
+        Ok(Some(manifest))
+    }
+
+    /// The manifest of the workspace containing `manifest_dir`
+    fn workspace(manifest_dir: &Path) -> syn::Result<Option<Rc<Self>>> {
+        for dir in manifest_dir.ancestors() {
+            let path = dir.join("Cargo.toml");
+            if path.is_file() {
+                let manifest = Self::load(&path)?;
+                if manifest.is_workspace {
+                    return Ok(Some(manifest));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn load(path: &Path) -> syn::Result<Rc<Self>> {
+        let error = |error: &dyn std::fmt::Display| {
+            syn::Error::new(
+                Span::call_site(),
+                format!("Failed to read '{}': {error}", path.display()),
+            )
+        };
+
+        let modified = fs::metadata(path).map_err(|e| error(&e))?.modified().ok();
+        if let Some(manifest) = MANIFESTS.with_borrow(|manifests| {
+            manifests
+                .get(path)
+                .filter(|(cached, _manifest)| modified.is_some() && *cached == modified)
+                .map(|(_modified, manifest)| manifest.clone())
+        }) {
+            return Ok(manifest);
+        }
+
+        let source = fs::read_to_string(path).map_err(|e| error(&e))?;
+        let manifest = Rc::new(Self::parse(&source).map_err(|e| error(&e))?);
+        MANIFESTS.with_borrow_mut(|manifests| {
+            manifests.insert(path.to_path_buf(), (modified, manifest.clone()));
+        });
+
+        Ok(manifest)
+    }
+
+    fn all_dependencies(&self) -> impl Iterator<Item = &Dependency> {
+        self.dependencies
+            .iter()
+            .chain(&self.dev_dependencies)
+            .chain(&self.build_dependencies)
+    }
+
+    /// The dependencies the crate being compiled can refer to.
+    /// Build scripts only have `[build-dependencies]`. Other targets have `[dependencies]`, and tests,
+    /// examples and benchmarks also have `[dev-dependencies]`. Which kind of target is being compiled is not known,
+    /// so `[dev-dependencies]` are included unless `[dependencies]` already has the same crate.
+    fn available_dependencies(&self, build_script: bool) -> Vec<&Dependency> {
+        if build_script {
+            return self.build_dependencies.iter().collect();
+        }
+
+        let mut dependencies: Vec<_> = self.dependencies.iter().collect();
+        dependencies.extend(self.dev_dependencies.iter().filter(|dev_dependency| {
+            !self
+                .dependencies
+                .iter()
+                .any(|dependency| dependency.crate_name() == dev_dependency.crate_name())
+        }));
+        dependencies
+    }
+
+    fn parse(source: &str) -> Result<Self, toml::de::Error> {
+        let table: Table = source.parse()?;
+
+        let name = |key: &str| {
+            table
+                .get(key)
+                .and_then(|value| value.get("name"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        let workspace = table.get("workspace").and_then(Value::as_table);
+        let mut manifest = Self {
+            name: name("lib").or_else(|| name("package")),
+            is_workspace: workspace.is_some(),
+            workspace_dependencies: workspace
+                .map(|workspace| dependencies(workspace, "dependencies"))
+                .unwrap_or_default(),
+            ..Self::default()
+        };
+
+        manifest.add_dependencies(&table);
+        if let Some(targets) = table.get("target").and_then(Value::as_table) {
+            for (spec, target) in targets {
+                if let Some(target) = target.as_table()
+                    && target_enabled(spec)
+                {
+                    manifest.add_dependencies(target);
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Add the dependencies of `table`, which is the manifest or one of its `[target]` tables
+    fn add_dependencies(&mut self, table: &Table) {
+        self.dependencies
+            .extend(dependencies(table, "dependencies"));
+        self.dev_dependencies
+            .extend(dependencies(table, "dev-dependencies"));
+        self.build_dependencies
+            .extend(dependencies(table, "build-dependencies"));
+    }
+}
+
+/// The dependencies in the `key` table of `table`
+fn dependencies(table: &Table, key: &str) -> Vec<Dependency> {
+    let Some(dependencies) = table.get(key).and_then(Value::as_table) else {
+        return vec![];
+    };
+
+    dependencies
+        .iter()
+        .map(|(name, value)| Dependency {
+            name: name.clone(),
+            package: value
+                .get("package")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            workspace: value
+                .get("workspace")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        })
+        .collect()
+}
+
+/// Whether a `[target.<spec>]` table applies, where `spec` is a `cfg(...)` predicate or a target triple.
+/// Proc macros are not told which target is being compiled for, so this is evaluated for the host,
+/// which is the target unless cross-compiling.
+fn target_enabled(spec: &str) -> bool {
+    match syn::parse_str::<Meta>(spec) {
+        Ok(Meta::List(list)) if list.path.is_ident("cfg") => list
+            .parse_args::<Meta>()
+            .is_ok_and(|predicate| cfg_enabled(&predicate)),
+        _ => {
+            let os = match env::consts::OS {
+                "macos" => "darwin",
+                os => os,
+            };
+            let mut parts = spec.split('-');
+            parts.next() == Some(env::consts::ARCH) && parts.any(|part| part == os)
+        }
+    }
+}
+
+/// Evaluate the predicate of a `cfg(...)` target table for the host
+fn cfg_enabled(predicate: &Meta) -> bool {
+    match predicate {
+        Meta::Path(path) => {
+            path.is_ident("unix") && cfg!(unix) || path.is_ident("windows") && cfg!(windows)
+        }
+        Meta::List(list) => {
+            let Ok(predicates) =
+                list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            else {
+                return false;
+            };
+
+            if list.path.is_ident("all") {
+                predicates.iter().all(cfg_enabled)
+            } else if list.path.is_ident("any") {
+                predicates.iter().any(cfg_enabled)
+            } else if list.path.is_ident("not") {
+                predicates.len() == 1 && !cfg_enabled(&predicates[0])
+            } else {
+                false
+            }
+        }
+        Meta::NameValue(name_value) => {
+            let Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) = &name_value.value
+            else {
+                return false;
+            };
+            let Some(key) = name_value.path.get_ident() else {
+                return false;
+            };
+            let value = value.value();
+
+            match key.to_string().as_str() {
+                "target_os" => value == env::consts::OS,
+                "target_family" => value == env::consts::FAMILY,
+                "target_arch" => value == env::consts::ARCH,
+                "target_pointer_width" => value == usize::BITS.to_string(),
+                "target_endian" => {
+                    value
+                        == if cfg!(target_endian = "big") {
+                            "big"
+                        } else {
+                            "little"
+                        }
+                }
+                "target_env" => match value.as_str() {
+                    "" => cfg!(target_env = ""),
+                    "gnu" => cfg!(target_env = "gnu"),
+                    "msvc" => cfg!(target_env = "msvc"),
+                    "musl" => cfg!(target_env = "musl"),
+                    _ => false,
+                },
+                "target_vendor" => match value.as_str() {
+                    "apple" => cfg!(target_vendor = "apple"),
+                    "pc" => cfg!(target_vendor = "pc"),
+                    "unknown" => cfg!(target_vendor = "unknown"),
+                    _ => false,
+                },
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn crate_names<'d>(
+        dependencies: impl IntoIterator<Item = &'d Dependency>,
+    ) -> Vec<(&'d str, String)> {
+        dependencies
+            .into_iter()
+            .map(|dependency| (dependency.name.as_str(), dependency.crate_name()))
+            .collect()
+    }
+
+    #[test]
+    fn parse() {
+        let manifest = Manifest::parse(
+            r#"
+            [package]
+            name = "my-crate"
+            description = """
+            [dependencies]
+            not_a_dependency = "1"
+            """
+            keywords = ["a \"quoted\" # keyword", 'literal # "']
+            exclude = [
+                "a",
+                [
+                    "[dependencies]",
+                ],
+            ]
+
+            [dependencies]
+            plain = "1.0" # comment
+            telety_renamed = { version = "0.4", package = "telety" }
+            "quoted-name" = { package = 'other-crate', features = ["a", "b"] }
+            inherited = { workspace = true }
+
+            [build-dependencies.build_renamed]
+            version = "1"
+            package = "build-crate"
+
+            [[bin]]
+            name = "not-the-crate"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("my-crate"));
+        assert!(!manifest.is_workspace);
+        assert_eq!(
+            crate_names(&manifest.dependencies),
+            [
+                ("inherited", "inherited".to_string()),
+                ("plain", "plain".to_string()),
+                ("quoted-name", "other_crate".to_string()),
+                ("telety_renamed", "telety".to_string()),
+            ]
+        );
+        assert!(manifest.dependencies[0].workspace);
+        assert_eq!(
+            crate_names(&manifest.build_dependencies),
+            [("build_renamed", "build_crate".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(Manifest::parse("[dependencies\nfoo = 1").is_err());
+    }
+
+    #[test]
+    fn parse_workspace() {
+        let manifest = Manifest::parse(
+            r#"
+            [workspace]
+            members = [
+                "a",
+                "b",
+            ]
+
+            [workspace.dependencies]
+            inherited = { version = "1", package = "inherited-core" }
+
+            [lib]
+            name = "lib_name"
+
+            [package]
+            name = "package-name"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("lib_name"));
+        assert!(manifest.is_workspace);
+        assert!(manifest.dependencies.is_empty());
+        assert_eq!(
+            manifest.workspace_dependencies,
+            [Dependency {
+                name: "inherited".to_string(),
+                package: Some("inherited-core".to_string()),
+                workspace: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn dependency_kinds() {
+        let manifest = Manifest::parse(
+            r#"
+            [dependencies]
+            bar = "1"
+
+            [dev-dependencies]
+            bar_renamed = { package = "bar" }
+            dev_renamed = { package = "dev-crate" }
+
+            [build-dependencies]
+            build_renamed = { package = "bar" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            crate_names(manifest.available_dependencies(false)),
+            [
+                ("bar", "bar".to_string()),
+                ("dev_renamed", "dev_crate".to_string()),
+            ]
+        );
+        assert_eq!(
+            crate_names(manifest.available_dependencies(true)),
+            [("build_renamed", "bar".to_string())]
+        );
+    }
+
+    #[test]
+    fn target_tables() {
+        let os = match env::consts::OS {
+            "macos" => "darwin",
+            os => os,
+        };
+        let manifest = Manifest::parse(&format!(
+            r#"
+            [target.'cfg(any(unix, windows))'.dependencies]
+            host_family = {{ package = "a" }}
+
+            [target.'cfg(all(target_arch = "{arch}", not(target_os = "none")))'.dev-dependencies]
+            host_arch = {{ package = "b" }}
+
+            [target.'cfg(target_os = "none")'.dependencies]
+            other_os = {{ package = "c" }}
+
+            [target.'cfg(not(target_pointer_width = "{width}"))'.dependencies]
+            other_width = {{ package = "d" }}
+
+            [target.{arch}-unknown-{os}-gnu.build-dependencies]
+            host_triple = {{ package = "e" }}
+
+            [target.not-a-triple.dependencies]
+            other_triple = {{ package = "f" }}
+            "#,
+            arch = env::consts::ARCH,
+            width = usize::BITS,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            crate_names(&manifest.dependencies),
+            [("host_family", "a".to_string())]
+        );
+        assert_eq!(
+            crate_names(&manifest.dev_dependencies),
+            [("host_arch", "b".to_string())]
+        );
+        assert_eq!(
+            crate_names(&manifest.build_dependencies),
+            [("host_triple", "e".to_string())]
+        );
+    }
+}
@@ -1,11 +1,12 @@
 use quote::{ToTokens, quote};
 use syn::{
     Attribute, Expr, ExprLit, Ident, Lit, MetaNameValue, Path, Token, Visibility, parse::Parse,
-    parse_quote, parse2, punctuated::Punctuated, spanned::Spanned as _,
+    parse2, punctuated::Punctuated, spanned::Spanned as _,
 };
 
-use crate::visitor;
+use crate::{Export, manifest, visitor};
 
+#[derive(Clone)]
 pub struct Options {
     pub module_path: Path,
     pub telety_path: Option<Path>,
@@ -13,6 +14,19 @@ pub struct Options {
     pub visibility: Option<Visibility>,
     pub proxy: Option<Path>,
     pub alias_traits: Option<bool>,
+    pub extensions: Vec<Path>,
+    /// Attributes which are removed from the emitted item, but kept in its definition.
+    pub helper_attrs: Vec<Ident>,
+    /// Which parts of the item are included in its definition.
+    pub export: Export,
+    /// The [alias::Map::fingerprint](crate::alias::Map::fingerprint) of the item when it was defined.
+    /// Only present in definitions provided by telety-generated macros.
+    #[doc(hidden)]
+    pub fingerprint: Option<u64>,
+    /// The value of each of the item's `cfg` predicates (see [Telety::cfg_predicates](crate::Telety::cfg_predicates)),
+    /// if they have been resolved. Only present in definitions provided by telety-generated macros.
+    #[doc(hidden)]
+    pub cfg: Option<Vec<bool>>,
 }
 
 impl Options {
@@ -41,21 +55,35 @@ impl Options {
         })
     }
 
+    /// The [Options::module_path], as the calling crate refers to it.
+    /// # Panics
+    /// Panics if the calling crate cannot be determined. See [Options::try_converted_containing_path].
+    #[deprecated(
+        note = "panics if the calling crate cannot be determined, use `Options::try_converted_containing_path` instead"
+    )]
     pub fn converted_containing_path(&self) -> Path {
+        self.try_converted_containing_path()
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// The [Options::module_path], as the calling crate refers to it.
+    pub fn try_converted_containing_path(&self) -> syn::Result<Path> {
         let mut containing_path = self.module_path.clone();
         directed_visit::visit_mut(
             &mut directed_visit::syn::direct::FullDefault,
-            &mut visitor::Crateify::new(),
+            &mut visitor::Crateify::try_new()?,
             &mut containing_path,
         );
 
-        containing_path
+        Ok(containing_path)
     }
 
+    /// The `telety_path` argument, or the path the calling crate refers to telety by
+    /// (see [manifest::telety_path]).
     pub fn telety_path(&self) -> Path {
         self.telety_path
             .clone()
-            .unwrap_or_else(|| parse_quote!(::telety))
+            .unwrap_or_else(manifest::telety_path)
     }
 }
 
@@ -64,7 +92,7 @@ impl Parse for Options {
         let mut module_path: Path = input.parse()?;
         directed_visit::visit_mut(
             &mut directed_visit::syn::direct::FullDefault,
-            &mut visitor::Decrateify::new(),
+            &mut visitor::Decrateify::try_new()?,
             &mut module_path,
         );
 
@@ -73,6 +101,11 @@ impl Parse for Options {
         let mut visibility = None;
         let mut proxy = None;
         let mut alias_traits = None;
+        let mut extensions = Vec::new();
+        let mut helper_attrs = Vec::new();
+        let mut export = Export::default();
+        let mut fingerprint = None;
+        let mut cfg = None;
 
         if let Some(_comma) = input.parse::<Option<Token![,]>>()? {
             let named_args: Punctuated<MetaNameValue, Token![,]> =
@@ -109,6 +142,45 @@ impl Parse for Options {
                                 "Expected \"always\" or \"never\"",
                             ));
                         }
+                    } else if ident == "extensions" {
+                        extensions.extend(
+                            value.parse_with(Punctuated::<Path, Token![,]>::parse_terminated)?,
+                        );
+                    } else if ident == "helper_attrs" {
+                        helper_attrs.extend(
+                            value.parse_with(Punctuated::<Ident, Token![,]>::parse_terminated)?,
+                        );
+                    } else if ident == "export" {
+                        if value.value() == "all" {
+                            export = Export::All;
+                        } else if value.value() == "public" {
+                            export = Export::Public;
+                        } else {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                "Expected \"all\" or \"public\"",
+                            ));
+                        }
+                    } else if ident == "fingerprint" {
+                        fingerprint =
+                            Some(u64::from_str_radix(&value.value(), 16).map_err(|_| {
+                                syn::Error::new(value.span(), "Expected a hexadecimal fingerprint")
+                            })?);
+                    } else if ident == "cfg" {
+                        cfg = Some(
+                            value
+                                .value()
+                                .chars()
+                                .map(|c| match c {
+                                    '0' => Ok(false),
+                                    '1' => Ok(true),
+                                    _ => Err(syn::Error::new(
+                                        value.span(),
+                                        "Expected a string of '0's and '1's",
+                                    )),
+                                })
+                                .collect::<syn::Result<_>>()?,
+                        );
                     } else {
                         return Err(syn::Error::new(
                             named_arg.path.span(),
@@ -131,6 +203,11 @@ impl Parse for Options {
             visibility,
             proxy,
             alias_traits,
+            extensions,
+            helper_attrs,
+            export,
+            fingerprint,
+            cfg,
         })
     }
 }
@@ -144,6 +221,11 @@ impl ToTokens for Options {
             visibility,
             proxy,
             alias_traits,
+            extensions,
+            helper_attrs,
+            export,
+            fingerprint,
+            cfg,
         } = self;
 
         // Convert to string literals
@@ -175,6 +257,37 @@ impl ToTokens for Options {
             .as_ref()
             .map(|always| if *always { "always" } else { "never" })
             .into_iter();
+        let extensions = (!extensions.is_empty())
+            .then(|| {
+                extensions
+                    .iter()
+                    .map(|extension| extension.to_token_stream().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .into_iter();
+        let helper_attrs = (!helper_attrs.is_empty())
+            .then(|| {
+                helper_attrs
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .into_iter();
+        let export = (*export == Export::Public).then_some("public").into_iter();
+        let fingerprint = fingerprint
+            .map(|fingerprint| format!("{fingerprint:016x}"))
+            .into_iter();
+        let cfg = cfg
+            .as_ref()
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|value| if *value { '1' } else { '0' })
+                    .collect::<String>()
+            })
+            .into_iter();
 
         quote!(
             #module_path
@@ -183,6 +296,11 @@ impl ToTokens for Options {
             #(, visibility = #visibility)*
             #(, proxy = #proxy)*
             #(, alias_traits = #alias_traits)*
+            #(, extensions = #extensions)*
+            #(, helper_attrs = #helper_attrs)*
+            #(, export = #export)*
+            #(, fingerprint = #fingerprint)*
+            #(, cfg = #cfg)*
         )
         .to_tokens(tokens);
     }
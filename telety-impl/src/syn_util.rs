@@ -1,10 +1,16 @@
-use proc_macro2::Ident;
+use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
 use syn::{
     Attribute, Expr, ExprPath, GenericArgument, GenericParam, Generics, Path, PathArguments,
     PathSegment, Token, Type, TypePath, VisRestricted, Visibility, parse_quote,
     parse_quote_spanned, punctuated::Punctuated,
 };
 
+/// Whether `ident` is `$crate`, as passed to a proc macro from within a `macro_rules!` macro.
+/// It refers to the crate defining the `macro_rules!` macro, whichever crate expands it.
+pub(crate) fn is_dollar_crate(ident: &Ident) -> bool {
+    ident == "$crate"
+}
+
 pub(crate) fn visibility_macro_export(visibility: &Visibility) -> Option<Attribute> {
     match visibility {
         Visibility::Public(vis_pub) => {
@@ -101,6 +107,60 @@ pub(crate) fn super_visibility(visibility: &Visibility) -> Visibility {
     }
 }
 
+/// A stable hash of tokens (FNV-1a), which does not change across Rust versions and platforms (unlike `DefaultHasher`).
+pub(crate) struct TokenHasher(u64);
+
+impl TokenHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    pub(crate) fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    /// `TokenStream::to_string` spacing differs between the compiler and proc-macro2's fallback,
+    /// so each token is hashed separately
+    pub(crate) fn write(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", ""),
+                    };
+                    self.write_str(open);
+                    self.write(group.stream());
+                    self.write_str(close);
+                }
+                TokenTree::Ident(ident) => self.write_str(&ident.to_string()),
+                TokenTree::Punct(punct) => self.write_str(&punct.as_char().to_string()),
+                TokenTree::Literal(literal) => self.write_str(&literal.to_string()),
+            }
+        }
+    }
+
+    /// The source location of `span`, which distinguishes otherwise identical tokens in different places.
+    /// Locations are only available inside a proc macro, elsewhere they are all the same.
+    pub(crate) fn write_location(&mut self, span: Span) {
+        let start = span.start();
+        self.write_str(&format!("{}:{}:{}", span.file(), start.line, start.column));
+    }
+
+    fn write_str(&mut self, text: &str) {
+        // Separate tokens so adjacent tokens cannot run together
+        for byte in text.bytes().chain([b' ']) {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod test_sublevel_visibility {
     use quote::ToTokens as _;
@@ -1,11 +1,16 @@
-use quote::format_ident;
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
 use syn::{
-    AngleBracketedGenericArguments, Attribute, GenericArgument, Ident, Item, Path, PathArguments,
-    PathSegment, Visibility, spanned::Spanned,
+    AngleBracketedGenericArguments, Attribute, GenericArgument, Ident, Item, Meta, Path,
+    PathArguments, PathSegment, Visibility,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
 };
 
 use crate::{
-    Options, alias,
+    Options, alias, cfg,
     item_data::{ItemData as _, Namespaces},
     syn_util, visitor,
 };
@@ -15,14 +20,30 @@ use crate::{
 pub struct Telety<'item> {
     options: Options,
     item: &'item Item,
+    /// The redacted [Item], if the `export` option excludes part of it
+    exported: Option<Item>,
+    /// The distinct `cfg` predicates of the parts of the (redacted) [Item]
+    cfg_predicates: Vec<Meta>,
+    /// The [Item] with its `cfg`s resolved, if the values of [Telety::cfg_predicates] are known
+    resolved: Option<Item>,
     alias_map: alias::Map<'static>,
     macro_ident: Ident,
     visibility: Visibility,
+    /// The [Options::module_path], as the calling crate refers to it
+    containing_mod_path: Path,
 }
 
 impl<'item> Telety<'item> {
     #[doc(hidden)]
     pub fn new_with_options(item: &'item Item, options: Options) -> syn::Result<Self> {
+        Self::new_with_options_and_alias_map(item, options, None)
+    }
+
+    fn new_with_options_and_alias_map(
+        item: &'item Item,
+        options: Options,
+        alias_map: Option<alias::Map<'static>>,
+    ) -> syn::Result<Self> {
         if let Some(ident) = item.ident()
             && ident.namespaces.contains(Namespaces::Macro)
         {
@@ -51,7 +72,47 @@ impl<'item> Telety<'item> {
             ));
         };
 
-        let unique_ident = Self::make_unique_ident(&options, &macro_ident);
+        let containing_mod_path = options.try_converted_containing_path()?;
+
+        let exported = options.export.redact(item);
+        let unresolved = exported.as_ref().unwrap_or(item);
+
+        let cfg_predicates = cfg::item_predicates(unresolved)?;
+        let resolved = options
+            .cfg
+            .as_ref()
+            .map(|values| cfg::resolve(unresolved, &cfg_predicates, values))
+            .transpose()?;
+
+        // Aliases are identified from the unresolved item, so they are the same under any cfg
+        let alias_map = match alias_map {
+            Some(alias_map) => alias_map,
+            None => {
+                Self::identify_aliases(unresolved, &options, &macro_ident, &containing_mod_path)?
+            }
+        };
+        Self::verify_fingerprint(item, &options, &alias_map)?;
+
+        Ok(Self {
+            options,
+            item,
+            exported,
+            cfg_predicates,
+            resolved,
+            alias_map,
+            macro_ident,
+            visibility,
+            containing_mod_path,
+        })
+    }
+
+    fn identify_aliases(
+        item: &Item,
+        options: &Options,
+        macro_ident: &Ident,
+        containing_mod_path: &Path,
+    ) -> syn::Result<alias::Map<'static>> {
+        let unique_ident = Self::make_unique_ident(options, macro_ident);
 
         let parameters = item.generics().cloned().unwrap_or_default();
 
@@ -63,7 +124,7 @@ impl<'item> Telety<'item> {
                 gt_token: Default::default(),
             });
             // Use the global path to alert user if the containing_path is incorrect
-            let mut path = options.converted_containing_path();
+            let mut path = containing_mod_path.clone();
             path.segments.push(PathSegment {
                 ident: item.ident().unwrap().ident.clone(),
                 arguments,
@@ -76,11 +137,12 @@ impl<'item> Telety<'item> {
 
         let mut alias_map = alias::Map::new_root(
             options.telety_path.clone(),
-            options.converted_containing_path(),
+            containing_mod_path.clone(),
+            options.module_path.clone(),
             module,
             parameters.clone(),
             unique_ident,
-            &options,
+            options,
         );
         alias_map.set_self(&self_type)?;
 
@@ -92,14 +154,35 @@ impl<'item> Telety<'item> {
             &mut identify_visitor,
             item,
         );
+        identify_visitor.into_result()?;
 
-        Ok(Self {
-            options,
-            item,
-            alias_map,
-            macro_ident,
-            visibility,
-        })
+        Ok(alias_map)
+    }
+
+    /// Definitions from a telety-generated macro record the fingerprint of the producer's [alias::Map].
+    /// If our map differs, the producer and consumer disagree on the aliases, and applying them would
+    /// silently refer to the wrong types.
+    fn verify_fingerprint(
+        item: &Item,
+        options: &Options,
+        alias_map: &alias::Map,
+    ) -> syn::Result<()> {
+        if let Some(expected) = options.fingerprint {
+            let actual = alias_map.fingerprint();
+            if expected != actual {
+                return Err(syn::Error::new(
+                    item.span(),
+                    format!(
+                        "Alias map fingerprint mismatch (expected {expected:016x}, found {actual:016x}). \
+                        The item was likely defined with a different version of telety than the one reading it. \
+                        Use compatible telety versions, or read the alias map with the v2 'alias_map' command \
+                        and `Telety::with_alias_map`"
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
     }
 
     /// Generate telety information for the [Item].
@@ -111,6 +194,25 @@ impl<'item> Telety<'item> {
         Self::new_with_options(item, options)
     }
 
+    /// Generate telety information for the [Item], using an [alias::Map] provided by the producer of the item
+    /// (see [alias::Map::from_args]) instead of identifying the aliases again.
+    /// The item must have a proper `#[telety(...)]` attribute.
+    pub fn with_alias_map(item: &'item Item, alias_map: alias::Map<'static>) -> syn::Result<Self> {
+        let options = Options::from_attrs(item.attrs())?;
+
+        Self::new_with_options_and_alias_map(item, options, Some(alias_map))
+    }
+
+    /// Generate telety information for the [Item], with the values of its [Telety::cfg_predicates]
+    /// as evaluated by the telety-generated macro.
+    #[doc(hidden)]
+    pub fn with_cfg_values(item: &'item Item, values: Vec<bool>) -> syn::Result<Self> {
+        let mut options = Options::from_attrs(item.attrs())?;
+        options.cfg = Some(values);
+
+        Self::new_with_options(item, options)
+    }
+
     pub fn options(&self) -> &Options {
         &self.options
     }
@@ -141,9 +243,9 @@ impl<'item> Telety<'item> {
         &self,
         generic_arguments: impl IntoIterator<Item = &'a GenericArgument>,
     ) -> syn::Result<visitor::ApplyGenericArguments<'_>> {
-        let Some(parameters) = self.item.generics() else {
+        let Some(parameters) = self.item().generics() else {
             return Err(syn::Error::new(
-                self.item.span(),
+                self.item().span(),
                 "Item kind does not have generic parameters",
             ));
         };
@@ -151,16 +253,33 @@ impl<'item> Telety<'item> {
         visitor::ApplyGenericArguments::new(parameters, generic_arguments)
     }
 
-    /// The [Item] this describes
+    /// The [Item] this describes, as it appears in its definition.
+    /// If the `export` argument of the telety attribute excludes part of the item, it is not included.
+    /// If the definition has its `cfg`s resolved (see the `cfg` argument of [v2::TY](crate::version::v2::TY)),
+    /// disabled fields, variants and associated items are not included.
     pub fn item(&self) -> &Item {
-        self.item
+        self.resolved
+            .as_ref()
+            .or(self.exported.as_ref())
+            .unwrap_or(self.item)
+    }
+
+    /// The [Item] as it appears in its definition, without its `cfg`s resolved
+    pub(crate) fn unresolved_item(&self) -> &Item {
+        self.exported.as_ref().unwrap_or(self.item)
+    }
+
+    /// The distinct `#[cfg(...)]` predicates of the item's fields, variants and associated items, in order of appearance.
+    /// The telety-generated macro evaluates these in the item's crate for commands with arguments.
+    pub fn cfg_predicates(&self) -> &[Meta] {
+        &self.cfg_predicates
     }
 
     /// The [Path] to the item, using the crate name, not the `crate::` qualifier,
     /// and no arguments on the item.
     pub fn path(&self) -> Path {
         let mut path = self.options.module_path.clone();
-        if let Some(ident) = self.item.ident() {
+        if let Some(ident) = self.item().ident() {
             path.segments.push(PathSegment {
                 ident: ident.ident.clone(),
                 arguments: PathArguments::None,
@@ -169,28 +288,143 @@ impl<'item> Telety<'item> {
         path
     }
 
+    /// The item definition along with its `#[telety]` attribute, which can be used to recreate this [Telety].
+    /// This is the definition provided by [v1::TY](crate::version::v1::TY), so its format must not change.
+    #[cfg(feature = "v1")]
+    pub(crate) fn definition(&self) -> TokenStream {
+        self.definition_with(false, false)
+    }
+
+    /// The item definition, as [Telety::definition], also recording the [alias::Map::fingerprint].
+    /// If `resolved`, the definition records the values of the `cfg` predicates (if known),
+    /// so the [Telety] recreated from it has its `cfg`s resolved.
+    pub(crate) fn fingerprinted_definition(&self, resolved: bool) -> TokenStream {
+        self.definition_with(true, resolved)
+    }
+
+    fn definition_with(&self, fingerprint: bool, resolved: bool) -> TokenStream {
+        let mut options = self.options.clone();
+        options.fingerprint = fingerprint.then(|| self.alias_map.fingerprint());
+        if !resolved {
+            options.cfg = None;
+        }
+        // A [Telety] recreated from a definition still has the original `#[telety(...)]` attribute
+        let mut item = Cow::Borrowed(self.unresolved_item());
+        if item
+            .attrs()
+            .iter()
+            .any(|attr| attr.path().is_ident("telety"))
+        {
+            item.to_mut()
+                .attrs_mut()
+                .retain(|attr| !attr.path().is_ident("telety"));
+        }
+        quote! {
+            #[telety(#options)]
+            #item
+        }
+    }
+
     /// The [Attribute]s on the [Item]
     pub fn attributes(&self) -> &[Attribute] {
-        self.item.attrs()
+        self.item().attrs()
+    }
+
+    /// The helper [Attribute]s on the [Item] (see the `helper_attrs` argument of the telety attribute).
+    /// Helper attributes on fields and variants are included in the output of
+    /// [v2::FIELDS](crate::version::v2::FIELDS) and [v2::VARIANTS](crate::version::v2::VARIANTS).
+    pub fn helper_attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.item()
+            .attrs()
+            .iter()
+            .filter(|attr| self.is_helper_attribute(attr))
+    }
+
+    /// Whether `attr` is one of the helper attributes named by the `helper_attrs` argument of the telety attribute
+    pub fn is_helper_attribute(&self, attr: &Attribute) -> bool {
+        self.options
+            .helper_attrs
+            .iter()
+            .any(|helper| attr.path().is_ident(helper))
+    }
+
+    /// The [Item] with its helper attributes, and those of its fields and variants, removed,
+    /// along with the `#[telety(...)]` attributes setting an [alias::Mode].
+    /// The compiler would reject these attributes, so this is emitted instead of the [Item].
+    #[doc(hidden)]
+    pub fn stripped_item(&self) -> Cow<'_, Item> {
+        let mut item = self.item.clone();
+        if self.strip_attributes(&mut item) {
+            Cow::Owned(item)
+        } else {
+            Cow::Borrowed(self.item)
+        }
+    }
+
+    /// Remove the attributes removed by [Telety::stripped_item] from `item`.
+    /// Returns whether any were removed.
+    pub(crate) fn strip_attributes(&self, item: &mut Item) -> bool {
+        let mut strip = StripAttributes {
+            telety: self,
+            changed: false,
+        };
+        strip.retain(item.attrs_mut(), true);
+        strip.visit_item_mut(item);
+        strip.changed
     }
 
     /// The [Path] of the module containing this [Item].
     /// Provided by argument to the telety attribute.
     pub fn containing_mod_path(&self) -> Path {
-        self.options.converted_containing_path()
+        self.containing_mod_path.clone()
     }
 
     pub fn macro_ident(&self) -> &Ident {
         &self.macro_ident
     }
 
+    /// The identifier of the macro evaluating the predicate at `index` of [Telety::cfg_predicates]
+    #[doc(hidden)]
+    pub fn cfg_macro_ident(&self, index: usize) -> Ident {
+        let unique_ident = self.alias_map.unique_ident();
+        format_ident!("{unique_ident}_telety_cfg_{index}")
+    }
+
+    /// The identifier the macro evaluating the predicate at `index` of [Telety::cfg_predicates]
+    /// is re-exported as, in the module containing the item
+    #[doc(hidden)]
+    pub fn cfg_macro_reexport_ident(&self, index: usize) -> Ident {
+        let macro_ident = &self.macro_ident;
+        format_ident!("__telety_cfg_{macro_ident}_{index}")
+    }
+
+    /// The path to the re-exported macro evaluating the predicate at `index` of [Telety::cfg_predicates],
+    /// from within the telety-generated macro
+    pub(crate) fn cfg_macro_path(&self, index: usize) -> TokenStream {
+        let mut segments = self.containing_mod_path().segments.into_iter();
+        let first = segments.next().map(|segment| {
+            if segment.ident == "crate" {
+                quote!($crate)
+            } else {
+                quote!(#segment)
+            }
+        });
+        let ident = self.cfg_macro_reexport_ident(index);
+        quote!(#first #(:: #segments)* :: #ident)
+    }
+
     fn module_path_ident(options: &Options) -> Ident {
         let mut iter = options.module_path.segments.iter();
-        let mut unique_ident = iter
+        let first = &iter
             .next()
             .expect("Path must have at least one segment")
-            .ident
-            .clone();
+            .ident;
+        // `$crate` is not valid within an identifier
+        let mut unique_ident = if syn_util::is_dollar_crate(first) {
+            Ident::new("__dollar_crate", first.span())
+        } else {
+            first.clone()
+        };
         for segment in iter {
             let i = &segment.ident;
             unique_ident = format_ident!("{unique_ident}_{i}");
@@ -203,3 +437,68 @@ impl<'item> Telety<'item> {
         format_ident!("{module_path_ident}_{suffix}")
     }
 }
+
+/// Removes helper attributes from the item, fields, and variants, and [alias::Mode] attributes
+/// from everywhere [visitor::IdentifyAliases] reads them.
+struct StripAttributes<'t, 'item> {
+    telety: &'t Telety<'item>,
+    changed: bool,
+}
+
+impl StripAttributes<'_, '_> {
+    fn retain(&mut self, attrs: &mut Vec<Attribute>, helpers: bool) {
+        let len = attrs.len();
+        attrs.retain(|attr| {
+            let strip = alias::Mode::is_mode_attribute(attr)
+                || (helpers && self.telety.is_helper_attribute(attr));
+            !strip
+        });
+        self.changed |= attrs.len() != len;
+    }
+}
+
+impl VisitMut for StripAttributes<'_, '_> {
+    fn visit_field_mut(&mut self, i: &mut syn::Field) {
+        self.retain(&mut i.attrs, true);
+        visit_mut::visit_field_mut(self, i);
+    }
+
+    fn visit_variant_mut(&mut self, i: &mut syn::Variant) {
+        self.retain(&mut i.attrs, true);
+        visit_mut::visit_variant_mut(self, i);
+    }
+
+    fn visit_trait_item_mut(&mut self, i: &mut syn::TraitItem) {
+        if let Some(attrs) = cfg::trait_item_attrs_mut(i) {
+            self.retain(attrs, false);
+        }
+        visit_mut::visit_trait_item_mut(self, i);
+    }
+
+    fn visit_impl_item_mut(&mut self, i: &mut syn::ImplItem) {
+        if let Some(attrs) = cfg::impl_item_attrs_mut(i) {
+            self.retain(attrs, false);
+        }
+        visit_mut::visit_impl_item_mut(self, i);
+    }
+
+    fn visit_type_param_mut(&mut self, i: &mut syn::TypeParam) {
+        self.retain(&mut i.attrs, false);
+        visit_mut::visit_type_param_mut(self, i);
+    }
+
+    fn visit_const_param_mut(&mut self, i: &mut syn::ConstParam) {
+        self.retain(&mut i.attrs, false);
+        visit_mut::visit_const_param_mut(self, i);
+    }
+
+    fn visit_pat_type_mut(&mut self, i: &mut syn::PatType) {
+        self.retain(&mut i.attrs, false);
+        visit_mut::visit_pat_type_mut(self, i);
+    }
+
+    fn visit_receiver_mut(&mut self, i: &mut syn::Receiver) {
+        self.retain(&mut i.attrs, false);
+        visit_mut::visit_receiver_mut(self, i);
+    }
+}
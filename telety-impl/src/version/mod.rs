@@ -5,9 +5,14 @@ pub mod v0;
 #[cfg(feature = "v1")]
 pub mod v1;
 
+#[cfg(feature = "v2")]
+pub mod v2;
+
 #[doc(hidden)]
 pub const VERSIONS: &[(usize, &[Command])] = &[
     (v0::VERSION, v0::COMMANDS),
     #[cfg(feature = "v1")]
     (v1::VERSION, v1::COMMANDS),
+    #[cfg(feature = "v2")]
+    (v2::VERSION, v2::COMMANDS),
 ];
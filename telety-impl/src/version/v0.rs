@@ -1,26 +1,21 @@
-use quote::quote;
-use syn::spanned::Spanned as _;
+use quote::{ToTokens as _, quote};
+use syn::{LitInt, spanned::Spanned as _};
 
-use crate::{Command, command::ParameterIdents};
+use crate::Command;
 
 pub(crate) const VERSION: usize = 0;
 
 /// Replaces `needle` with the path to this item.
-pub const PATH: Command = Command::new(VERSION, "path", |ty| {
-    let ParameterIdents {
-        needle, haystack, ..
-    } = ParameterIdents::new(ty.item().span());
-    let replacement = ty.path();
+pub const PATH: Command = Command::new(VERSION, "path", |ty| Some(ty.path().into_token_stream()));
 
-    let telety_path = ty.options().telety_path();
-
-    Some(quote! {
-        #telety_path::__private::find_and_replace! {
-            $#needle,
-            [#replacement],
-            $($#haystack)*
-        }
-    })
+/// Replaces `needle` with the list of [Command] versions supported by this item (e.g. `[0, 1]`).  
+/// The supported versions are determined by the telety features enabled when the item was compiled.
+pub const VERSIONS: Command = Command::new(VERSION, "versions", |ty| {
+    let span = ty.item().span();
+    let versions = crate::version::VERSIONS
+        .iter()
+        .map(|(version, _commands)| LitInt::new(&version.to_string(), span));
+    Some(quote!([#(#versions),*]))
 });
 
-pub(crate) const COMMANDS: &[Command] = &[PATH];
+pub(crate) const COMMANDS: &[Command] = &[PATH, VERSIONS];
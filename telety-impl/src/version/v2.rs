@@ -0,0 +1,277 @@
+use proc_macro2::TokenStream;
+use quote::{ToTokens as _, quote};
+use syn::{
+    Attribute, Fields, GenericArgument, Generics, Ident, Item, Member, Path, Token, bracketed,
+    parse::{ParseStream, Parser as _},
+    punctuated::Punctuated,
+    spanned::Spanned as _,
+};
+
+use crate::{Command, Telety, item_data::ItemData as _};
+
+pub(crate) const VERSION: usize = 2;
+
+/// Replaces `needle` with the full definition of the item.
+/// ## Arguments
+/// * `generics = [Arg0, Arg1, ...]` - Substitute the generic parameters of the item with the provided arguments.
+///   Types in the definition are replaced with their aliases, and the generic parameters are removed,
+///   so the output is not suitable for [Telety::new]. Helper attributes and [alias::Mode](crate::alias::Mode)
+///   attributes are removed.
+/// * `cfg = preserved` or `cfg = resolved` - Whether the `#[cfg(...)]`s of fields, variants and associated items
+///   are kept (the default), or evaluated in the item's crate. Resolved definitions record the value of each
+///   predicate, and [Telety::item] only includes the enabled parts. With `generics`, disabled parts are removed
+///   from the output.
+pub const TY: Command = Command::new(VERSION, "ty", |ty| Some(ty.fingerprinted_definition(false)))
+    .with_arguments(|ty, args| {
+        let (generics, resolved) = (|input: ParseStream| {
+            let mut generics = None;
+            let mut resolved = None;
+            parse_arguments(input, &["generics", "cfg"], |name, input| {
+                let _eq: Token![=] = input.parse()?;
+                let duplicate = if name == "generics" {
+                    let content;
+                    bracketed!(content in input);
+                    let arguments =
+                        Punctuated::<GenericArgument, Token![,]>::parse_terminated(&content)?;
+                    generics.replace(arguments).is_some()
+                } else {
+                    let value: Ident = input.parse()?;
+                    let value = if value == "resolved" {
+                        true
+                    } else if value == "preserved" {
+                        false
+                    } else {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "Expected 'resolved' or 'preserved'",
+                        ));
+                    };
+                    resolved.replace(value).is_some()
+                };
+                if duplicate {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("Argument '{name}' was already provided"),
+                    ));
+                }
+                Ok(())
+            })?;
+            Ok((generics, resolved.unwrap_or(false)))
+        })
+        .parse2(args)?;
+
+        if let Some(generics) = generics {
+            let mut item = if resolved {
+                ty.item().clone()
+            } else {
+                ty.unresolved_item().clone()
+            };
+            ty.strip_attributes(&mut item);
+            item.attrs_mut()
+                .retain(|attr| !attr.path().is_ident("telety"));
+
+            directed_visit::visit_mut(
+                &mut directed_visit::syn::direct::FullDefault,
+                &mut ty.alias_map().visitor(),
+                &mut item,
+            );
+
+            let mut generics_visitor = ty.generics_visitor(&generics)?;
+            directed_visit::visit_mut(
+                &mut directed_visit::syn::direct::FullDefault,
+                &mut generics_visitor,
+                &mut item,
+            );
+
+            if let Some(item_generics) = item.generics_mut() {
+                *item_generics = Generics::default();
+            }
+
+            Ok(item.into_token_stream())
+        } else {
+            Ok(ty.fingerprinted_definition(resolved))
+        }
+    });
+
+/// Requires an argument. Replaces `needle` with the aliased type of a field of a struct or union.
+/// ## Arguments
+/// * `name` or `index` - The field of the item.
+pub const FIELD: Command = Command::new(VERSION, "field", |_ty| None).with_arguments(|ty, args| {
+    let member: Member = syn::parse2(args)?;
+
+    let fields: Vec<_> = match ty.item() {
+        Item::Struct(item_struct) => item_struct.fields.iter().collect(),
+        Item::Union(item_union) => item_union.fields.named.iter().collect(),
+        item => {
+            return Err(syn::Error::new(
+                item.span(),
+                "Command 'field' is only supported for structs and unions",
+            ));
+        }
+    };
+
+    let field = fields
+        .into_iter()
+        .enumerate()
+        .find(|(index, field)| match &member {
+            Member::Named(ident) => field.ident.as_ref() == Some(ident),
+            Member::Unnamed(field_index) => field_index.index as usize == *index,
+        });
+
+    let Some((_index, field)) = field else {
+        return Err(syn::Error::new(
+            member.span(),
+            format!("No field '{}'", member.to_token_stream()),
+        ));
+    };
+
+    let mut field_ty = field.ty.clone();
+    directed_visit::visit_mut(
+        &mut directed_visit::syn::direct::FullDefault,
+        &mut ty.alias_map().visitor(),
+        &mut field_ty,
+    );
+
+    Ok(field_ty.into_token_stream())
+});
+
+/// Replaces `needle` with the fields of a struct or union, using aliased types.
+/// The output can be matched by a `macro_rules!` macro:
+/// * Named fields - `{ name0: Type0, name1: Type1 }`, matched by `{ $($name:ident : $ty:ty),* }`
+/// * Unnamed fields - `( 0: Type0, 1: Type1 )`, matched by `( $($index:tt : $ty:ty),* )`
+/// * Unit - `;`
+///
+/// Helper attributes (see [Telety::helper_attributes]) precede their field, matched by `$(#[$attr:meta])*`.
+pub const FIELDS: Command = Command::new(VERSION, "fields", |ty| match ty.item() {
+    Item::Struct(item_struct) => Some(fields(ty, &item_struct.fields)),
+    Item::Union(item_union) => Some(fields(ty, &Fields::Named(item_union.fields.clone()))),
+    _ => None,
+});
+
+/// Replaces `needle` with the variants of an enum. Each variant has its identifier, its fields
+/// (in the same format as [FIELDS]), and its explicit discriminant, if any.
+/// The output can be matched by a `macro_rules!` macro with `{ $($variant:ident $fields:tt $(= $discriminant:expr)?),* }`.
+/// ## Example
+/// ```rust,ignore
+/// enum MyEnum {
+///     A,
+///     B(i32),
+///     C { c: i32 } = 5,
+/// }
+/// ```
+/// becomes `{ A ;, B ( 0: i32 ), C { c: i32 } = 5 }`, with aliased types.  
+/// Discriminant expressions are not aliased.
+/// Helper attributes (see [Telety::helper_attributes]) precede their variant, like fields in [FIELDS].
+pub const VARIANTS: Command = Command::new(VERSION, "variants", |ty| {
+    let Item::Enum(item_enum) = ty.item() else {
+        return None;
+    };
+
+    let variants = item_enum.variants.iter().map(|variant| {
+        let helper_attrs = variant
+            .attrs
+            .iter()
+            .filter(|attr| ty.is_helper_attribute(attr));
+        let ident = &variant.ident;
+        let fields = fields(ty, &variant.fields);
+        let discriminant = variant
+            .discriminant
+            .as_ref()
+            .map(|(eq, expr)| quote!(#eq #expr));
+        quote!(#(#helper_attrs)* #ident #fields #discriminant)
+    });
+
+    Some(quote!({ #(#variants),* }))
+});
+
+/// Replaces `needle` with the [alias::Map](crate::alias::Map) of the item, in the format of
+/// [interop::AliasMapArgs](crate::interop::AliasMapArgs).
+/// Use [alias::Map::from_args](crate::alias::Map::from_args) and [Telety::with_alias_map]
+/// to read the item with the aliases identified by the item's telety version.
+pub const ALIAS_MAP: Command = Command::new(VERSION, "alias_map", |ty| {
+    Some(ty.alias_map().to_args().into_token_stream())
+});
+
+/// Replaces `needle` with the attributes of the item (excluding `#[telety]`).
+/// ## Arguments
+/// * `path0, path1, ...` - Only include attributes with one of the provided paths (e.g. `attrs(serde, doc)`).
+pub const ATTRS: Command = Command::new(VERSION, "attrs", |ty| {
+    let attrs = filter_attrs(ty, |_attr| true);
+    Some(quote!(#(#attrs)*))
+})
+.with_arguments(|ty, args| {
+    let paths = Punctuated::<Path, Token![,]>::parse_terminated.parse2(args)?;
+    let attrs = filter_attrs(ty, |attr| paths.iter().any(|path| attr.path() == path));
+    Ok(quote!(#(#attrs)*))
+});
+
+fn fields(ty: &Telety, fields: &Fields) -> TokenStream {
+    let entries = fields.iter().enumerate().map(|(index, field)| {
+        let member = field
+            .ident
+            .clone()
+            .map_or_else(|| Member::from(index), Member::Named);
+
+        let mut field_ty = field.ty.clone();
+        directed_visit::visit_mut(
+            &mut directed_visit::syn::direct::FullDefault,
+            &mut ty.alias_map().visitor(),
+            &mut field_ty,
+        );
+
+        let helper_attrs = field
+            .attrs
+            .iter()
+            .filter(|attr| ty.is_helper_attribute(attr));
+
+        quote!(#(#helper_attrs)* #member: #field_ty)
+    });
+
+    match fields {
+        Fields::Named(_) => quote!({ #(#entries),* }),
+        Fields::Unnamed(_) => quote!(( #(#entries),* )),
+        Fields::Unit => quote!(;),
+    }
+}
+
+fn filter_attrs<'t>(
+    ty: &'t Telety,
+    mut filter: impl FnMut(&Attribute) -> bool,
+) -> impl Iterator<Item = &'t Attribute> {
+    ty.attributes()
+        .iter()
+        .filter(move |attr| !attr.path().is_ident("telety") && filter(attr))
+}
+
+/// Parses comma-separated arguments, each beginning with one of the identifiers in `names`.
+/// `parse_value` parses the remainder of the argument.
+fn parse_arguments(
+    input: ParseStream,
+    names: &[&str],
+    mut parse_value: impl FnMut(&Ident, ParseStream) -> syn::Result<()>,
+) -> syn::Result<()> {
+    while !input.is_empty() {
+        let name: Ident = input.parse()?;
+        if !names.iter().any(|n| name == n) {
+            let expected = names
+                .iter()
+                .map(|n| format!("'{n}'"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(syn::Error::new(
+                name.span(),
+                format!("Unknown argument '{name}', expected one of: {expected}"),
+            ));
+        }
+
+        parse_value(&name, input)?;
+
+        if !input.is_empty() {
+            let _comma: Token![,] = input.parse()?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) const COMMANDS: &[Command] = &[TY, FIELD, FIELDS, VARIANTS, ATTRS, ALIAS_MAP];
@@ -1,24 +1,73 @@
-use proc_macro2::Span;
 use syn::{Ident, PathArguments, PathSegment, UseTree};
 
-use super::calling_crate;
+use crate::manifest;
 
-pub struct Crateify(PathSegment);
+/// Replaces a leading crate name in paths with `crate`, the inverse of [Decrateify](super::Decrateify).
+/// `$crate` is left as is, as the crate it refers to may not be the calling crate.
+/// Global paths to renamed dependencies of the calling crate use the name they are renamed to.
+pub struct Crateify {
+    /// [None] if the calling crate cannot be referred to by name (i.e. in doctests)
+    crate_segment: Option<PathSegment>,
+    /// Pairs of crate names and the names the calling crate refers to them by
+    renames: Vec<(Ident, Ident)>,
+}
 
 impl Crateify {
+    /// # Panics
+    /// Panics if the calling crate cannot be determined. See [Crateify::try_new].
+    #[deprecated(
+        note = "panics if the calling crate cannot be determined, use `Crateify::try_new` instead"
+    )]
     pub fn new() -> Self {
-        Self::new_as_crate(calling_crate(Span::call_site()))
+        Self::try_new().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Create a [Crateify] for the calling crate, with the names and renamed dependencies
+    /// found from the environment and the crate's `Cargo.toml`.
+    /// In doctests, which other crates cannot refer to, `crate` is left as is.
+    pub fn try_new() -> syn::Result<Self> {
+        Ok(Self {
+            crate_segment: manifest::crate_name()?.map(Self::segment),
+            renames: manifest::renames()?,
+        })
     }
 
     pub fn new_as_crate(crate_ident: Ident) -> Self {
-        Self(PathSegment {
-            ident: crate_ident,
+        Self {
+            crate_segment: Some(Self::segment(crate_ident)),
+            renames: vec![],
+        }
+    }
+
+    fn segment(ident: Ident) -> PathSegment {
+        PathSegment {
+            ident,
             arguments: PathArguments::None,
-        })
+        }
+    }
+
+    /// Refer to the crates named by the first of each pair by the second of the pair instead
+    pub fn with_renames(mut self, renames: Vec<(Ident, Ident)>) -> Self {
+        self.renames = renames;
+        self
+    }
+
+    fn rename(&self, ident: &Ident) -> Option<Ident> {
+        self.renames
+            .iter()
+            .find(|(crate_name, _renamed)| crate_name == ident)
+            .map(|(_crate_name, renamed)| {
+                let mut renamed = renamed.clone();
+                renamed.set_span(ident.span());
+                renamed
+            })
     }
 }
 
+/// # Panics
+/// Panics if the calling crate cannot be determined. See [Crateify::try_new].
 impl Default for Crateify {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self::new()
     }
@@ -29,11 +78,15 @@ impl directed_visit::syn::visit::FullMut for Crateify {
     where
         D: directed_visit::DirectMut<Self, syn::Path> + ?Sized,
     {
-        if let Some(first_segment) = node.segments.first_mut()
-            && *first_segment == visitor.0
-        {
-            first_segment.ident = Ident::new("crate", first_segment.ident.span());
-            node.leading_colon = None;
+        if let Some(first_segment) = node.segments.first_mut() {
+            if visitor.crate_segment.as_ref() == Some(first_segment) {
+                first_segment.ident = Ident::new("crate", first_segment.ident.span());
+                node.leading_colon = None;
+            } else if node.leading_colon.is_some()
+                && let Some(renamed) = visitor.rename(&first_segment.ident)
+            {
+                first_segment.ident = renamed;
+            }
         }
 
         directed_visit::Visitor::visit_mut(visitor, node);
@@ -43,11 +96,19 @@ impl directed_visit::syn::visit::FullMut for Crateify {
     where
         D: directed_visit::DirectMut<Self, syn::ItemUse> + ?Sized,
     {
-        if let UseTree::Path(path) = &mut node.tree
-            && path.ident == visitor.0.ident
-        {
-            path.ident = Ident::new("crate", path.ident.span());
-            node.leading_colon = None;
+        if let UseTree::Path(path) = &mut node.tree {
+            if visitor
+                .crate_segment
+                .as_ref()
+                .is_some_and(|segment| path.ident == segment.ident)
+            {
+                path.ident = Ident::new("crate", path.ident.span());
+                node.leading_colon = None;
+            } else if node.leading_colon.is_some()
+                && let Some(renamed) = visitor.rename(&path.ident)
+            {
+                path.ident = renamed;
+            }
         }
 
         directed_visit::Visitor::visit_mut(visitor, node);
@@ -1,24 +1,73 @@
-use proc_macro2::Span;
 use syn::{Ident, PathArguments, PathSegment, UseTree};
 
-use super::calling_crate;
+use crate::manifest;
 
-pub struct Decrateify(PathSegment);
+/// Replaces a leading `crate` in paths with the name of the calling crate.
+/// `$crate` already refers to the same crate wherever it is used, so it is left as is.
+/// Global paths to renamed dependencies of the calling crate use their crate name instead.
+pub struct Decrateify {
+    /// [None] if the calling crate cannot be referred to by name (i.e. in doctests)
+    crate_segment: Option<PathSegment>,
+    /// Pairs of crate names and the names the calling crate refers to them by
+    renames: Vec<(Ident, Ident)>,
+}
 
 impl Decrateify {
+    /// # Panics
+    /// Panics if the calling crate cannot be determined. See [Decrateify::try_new].
+    #[deprecated(
+        note = "panics if the calling crate cannot be determined, use `Decrateify::try_new` instead"
+    )]
     pub fn new() -> Self {
-        Self::new_as_crate(calling_crate(Span::call_site()))
+        Self::try_new().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Create a [Decrateify] for the calling crate, with the names and renamed dependencies
+    /// found from the environment and the crate's `Cargo.toml`.
+    /// In doctests, which other crates cannot refer to, `crate` is left as is.
+    pub fn try_new() -> syn::Result<Self> {
+        Ok(Self {
+            crate_segment: manifest::crate_name()?.map(Self::segment),
+            renames: manifest::renames()?,
+        })
     }
 
     pub fn new_as_crate(crate_ident: Ident) -> Self {
-        Self(PathSegment {
-            ident: crate_ident,
+        Self {
+            crate_segment: Some(Self::segment(crate_ident)),
+            renames: vec![],
+        }
+    }
+
+    fn segment(ident: Ident) -> PathSegment {
+        PathSegment {
+            ident,
             arguments: PathArguments::None,
-        })
+        }
+    }
+
+    /// The crates named by the first of each pair are referred to by the second of the pair
+    pub fn with_renames(mut self, renames: Vec<(Ident, Ident)>) -> Self {
+        self.renames = renames;
+        self
+    }
+
+    fn unrename(&self, ident: &Ident) -> Option<Ident> {
+        self.renames
+            .iter()
+            .find(|(_crate_name, renamed)| renamed == ident)
+            .map(|(crate_name, _renamed)| {
+                let mut crate_name = crate_name.clone();
+                crate_name.set_span(ident.span());
+                crate_name
+            })
     }
 }
 
+/// # Panics
+/// Panics if the calling crate cannot be determined. See [Decrateify::try_new].
 impl Default for Decrateify {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self::new()
     }
@@ -29,13 +78,19 @@ impl directed_visit::syn::visit::FullMut for Decrateify {
     where
         D: directed_visit::DirectMut<Self, syn::Path> + ?Sized,
     {
-        if let Some(first_segment) = node.segments.first_mut()
-            && first_segment.ident == "crate"
-        {
-            let mut segment = visitor.0.clone();
-            segment.ident.set_span(first_segment.ident.span());
-            *first_segment = segment;
-            node.leading_colon = Some(Default::default());
+        if let Some(first_segment) = node.segments.first_mut() {
+            if first_segment.ident == "crate"
+                && let Some(segment) = &visitor.crate_segment
+            {
+                let mut segment = segment.clone();
+                segment.ident.set_span(first_segment.ident.span());
+                *first_segment = segment;
+                node.leading_colon = Some(Default::default());
+            } else if node.leading_colon.is_some()
+                && let Some(crate_name) = visitor.unrename(&first_segment.ident)
+            {
+                first_segment.ident = crate_name;
+            }
         }
 
         directed_visit::Visitor::visit_mut(visitor, node);
@@ -45,13 +100,19 @@ impl directed_visit::syn::visit::FullMut for Decrateify {
     where
         D: directed_visit::DirectMut<Self, syn::ItemUse> + ?Sized,
     {
-        if let UseTree::Path(path) = &mut node.tree
-            && path.ident == "crate"
-        {
-            let mut ident = visitor.0.ident.clone();
-            ident.set_span(path.ident.span());
-            path.ident = ident;
-            node.leading_colon = Some(Default::default());
+        if let UseTree::Path(path) = &mut node.tree {
+            if path.ident == "crate"
+                && let Some(segment) = &visitor.crate_segment
+            {
+                let mut ident = segment.ident.clone();
+                ident.set_span(path.ident.span());
+                path.ident = ident;
+                node.leading_colon = Some(Default::default());
+            } else if node.leading_colon.is_some()
+                && let Some(crate_name) = visitor.unrename(&path.ident)
+            {
+                path.ident = crate_name;
+            }
         }
 
         directed_visit::Visitor::visit_mut(visitor, node);
@@ -1,10 +1,18 @@
 use std::collections::HashSet;
 
+use directed_visit::syn::direct::FullDefault;
+
 use crate::alias;
 
 pub struct IdentifyAliases<'m, 'map> {
     alias_map: &'m mut alias::Map<'map>,
     parameters: HashSet<syn::Ident>,
+    mode: alias::Mode,
+    /// The `cfg` predicates of the nodes we are within
+    cfg: Vec<syn::Meta>,
+    /// Whether this visitor was created for a node which has already been checked for attributes
+    entered: bool,
+    errors: Vec<syn::Error>,
 }
 
 impl<'m, 'map> IdentifyAliases<'m, 'map> {
@@ -24,12 +32,93 @@ impl<'m, 'map> IdentifyAliases<'m, 'map> {
         Self {
             alias_map,
             parameters,
+            mode: alias::Mode::Default,
+            cfg: vec![],
+            entered: false,
+            errors: vec![],
+        }
+    }
+
+    /// Errors from invalid `#[telety(...)]` attributes, and types which could not be aliased
+    /// under [alias::Mode::Alias], combined into one error.
+    pub fn into_result(self) -> syn::Result<()> {
+        let mut errors = self.errors.into_iter();
+        match errors.next() {
+            Some(mut error) => {
+                error.extend(errors);
+                Err(error)
+            }
+            None => Ok(()),
         }
     }
 
     fn is_parameter(&self, ident: &syn::Ident) -> bool {
         self.parameters.contains(ident)
     }
+
+    /// Visit `node` with `mode` instead of our own [alias::Mode], and within the additional `cfg` predicate
+    fn visit_with<N>(&mut self, mode: alias::Mode, cfg: Option<syn::Meta>, node: &N)
+    where
+        FullDefault: for<'a, 'b> directed_visit::Direct<IdentifyAliases<'a, 'b>, N>,
+        for<'a, 'b> IdentifyAliases<'a, 'b>: directed_visit::Visit<N>,
+    {
+        let mut visitor = IdentifyAliases {
+            alias_map: &mut *self.alias_map,
+            parameters: self.parameters.clone(),
+            mode,
+            cfg: self.cfg.iter().cloned().chain(cfg).collect(),
+            entered: true,
+            errors: vec![],
+        };
+        directed_visit::visit(&mut FullDefault, &mut visitor, node);
+        self.errors.append(&mut visitor.errors);
+    }
+
+    /// Visit a node which may have attributes setting its [alias::Mode] or `cfg` predicate
+    fn visit_attributed<D, N>(
+        mut visitor: directed_visit::Visitor<'_, D, Self>,
+        attrs: &[syn::Attribute],
+        node: &N,
+    ) where
+        D: directed_visit::Direct<Self, N> + ?Sized,
+        FullDefault: for<'a, 'b> directed_visit::Direct<IdentifyAliases<'a, 'b>, N>,
+        for<'a, 'b> IdentifyAliases<'a, 'b>: directed_visit::Visit<N>,
+    {
+        if std::mem::take(&mut visitor.entered) {
+            directed_visit::Visitor::visit(visitor, node);
+            return;
+        }
+
+        let mode = alias::Mode::from_attrs(attrs).unwrap_or_else(|error| {
+            visitor.errors.push(error);
+            None
+        });
+        let cfg = crate::cfg::predicate(attrs).unwrap_or_else(|error| {
+            visitor.errors.push(error);
+            None
+        });
+
+        match (mode, cfg) {
+            (None, None) => directed_visit::Visitor::visit(visitor, node),
+            (mode, cfg) => {
+                let mode = mode.unwrap_or(visitor.mode);
+                visitor.visit_with(mode, cfg, node);
+            }
+        }
+    }
+
+    fn cfg(&self) -> Option<syn::Meta> {
+        crate::cfg::all(self.cfg.iter().cloned())
+    }
+
+    fn record(&mut self, result: Result<bool, alias::Error>) {
+        // Outside of `Mode::Alias`, we just alias everything we are able to
+        if let Err(error) = result
+            && self.mode == alias::Mode::Alias
+        {
+            self.errors.push(error.into());
+        }
+    }
 }
 
 impl<'m, 'map> directed_visit::syn::visit::Full for IdentifyAliases<'m, 'map> {
@@ -59,6 +148,64 @@ impl<'m, 'map> directed_visit::syn::visit::Full for IdentifyAliases<'m, 'map> {
         }
     }
 
+    fn visit_field<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::Field)
+    where
+        D: directed_visit::Direct<Self, syn::Field> + ?Sized,
+    {
+        Self::visit_attributed(visitor, &node.attrs, node);
+    }
+
+    fn visit_variant<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::Variant)
+    where
+        D: directed_visit::Direct<Self, syn::Variant> + ?Sized,
+    {
+        Self::visit_attributed(visitor, &node.attrs, node);
+    }
+
+    fn visit_type_param<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::TypeParam)
+    where
+        D: directed_visit::Direct<Self, syn::TypeParam> + ?Sized,
+    {
+        Self::visit_attributed(visitor, &node.attrs, node);
+    }
+
+    fn visit_const_param<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::ConstParam)
+    where
+        D: directed_visit::Direct<Self, syn::ConstParam> + ?Sized,
+    {
+        Self::visit_attributed(visitor, &node.attrs, node);
+    }
+
+    fn visit_pat_type<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::PatType)
+    where
+        D: directed_visit::Direct<Self, syn::PatType> + ?Sized,
+    {
+        Self::visit_attributed(visitor, &node.attrs, node);
+    }
+
+    fn visit_receiver<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::Receiver)
+    where
+        D: directed_visit::Direct<Self, syn::Receiver> + ?Sized,
+    {
+        Self::visit_attributed(visitor, &node.attrs, node);
+    }
+
+    fn visit_trait_item<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::TraitItem)
+    where
+        D: directed_visit::Direct<Self, syn::TraitItem> + ?Sized,
+    {
+        let attrs = crate::cfg::trait_item_attrs(node).map_or(&[][..], Vec::as_slice);
+        Self::visit_attributed(visitor, attrs, node);
+    }
+
+    fn visit_impl_item<D>(visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::ImplItem)
+    where
+        D: directed_visit::Direct<Self, syn::ImplItem> + ?Sized,
+    {
+        let attrs = crate::cfg::impl_item_attrs(node).map_or(&[][..], Vec::as_slice);
+        Self::visit_attributed(visitor, attrs, node);
+    }
+
     fn visit_type_path<D>(mut visitor: directed_visit::Visitor<'_, D, Self>, node: &syn::TypePath)
     where
         D: directed_visit::Direct<Self, syn::TypePath> + ?Sized,
@@ -71,10 +218,25 @@ impl<'m, 'map> directed_visit::syn::visit::Full for IdentifyAliases<'m, 'map> {
             return;
         }
 
-        // No error handling, we just alias everything we are able to
-        let _ = visitor.alias_map.insert_type(node);
+        match visitor.mode {
+            alias::Mode::Skip => {}
+            alias::Mode::Builtin => {
+                // Only the outermost type is built in, alias the types within it as usual
+                if let Some(qself) = &node.qself {
+                    visitor.visit_with(alias::Mode::Default, None, &*qself.ty);
+                }
+                visitor.visit_with(alias::Mode::Default, None, &node.path);
+            }
+            mode => {
+                let cfg = visitor.cfg();
+                let result = visitor
+                    .alias_map
+                    .insert_type_with_cfg(node, mode, cfg.as_ref());
+                visitor.record(result);
 
-        directed_visit::Visitor::visit(visitor, node);
+                directed_visit::Visitor::visit(visitor, node);
+            }
+        }
     }
 
     fn visit_trait_bound<D>(
@@ -83,8 +245,21 @@ impl<'m, 'map> directed_visit::syn::visit::Full for IdentifyAliases<'m, 'map> {
     ) where
         D: directed_visit::Direct<Self, syn::TraitBound> + ?Sized,
     {
-        // No error handling, we just alias everything we are able to
-        let _ = visitor.alias_map.insert_trait(&node.path);
-        directed_visit::Visitor::visit(visitor, node);
+        match visitor.mode {
+            alias::Mode::Skip => {}
+            alias::Mode::Builtin => {
+                visitor.visit_with(alias::Mode::Default, None, &node.path);
+            }
+            mode => {
+                let cfg = visitor.cfg();
+                let result =
+                    visitor
+                        .alias_map
+                        .insert_trait_with_cfg(&node.path, mode, cfg.as_ref());
+                visitor.record(result);
+
+                directed_visit::Visitor::visit(visitor, node);
+            }
+        }
     }
 }
@@ -0,0 +1,98 @@
+use proc_macro2::{Group, TokenStream};
+use syn::{
+    Ident, Item, LitBool, LitInt, Token, bracketed,
+    parse::{Parse, ParseStream},
+    parse2, token,
+};
+use telety_impl::{Command, Telety, find_and_replace::SingleToken};
+
+struct ApplyArgumentsArgs {
+    cfg: Option<Vec<bool>>,
+    version: LitInt,
+    _comma0: Token![,],
+    keyword: Ident,
+    _comma1: Token![,],
+    definition: Group,
+    _comma2: Token![,],
+    args: Group,
+    _comma3: Token![,],
+    needle: SingleToken,
+    _comma4: Token![,],
+    haystack: TokenStream,
+}
+
+impl ApplyArgumentsArgs {
+    pub fn apply_arguments(self) -> syn::Result<TokenStream> {
+        let Self {
+            cfg,
+            version,
+            keyword,
+            definition,
+            args,
+            needle,
+            haystack,
+            ..
+        } = self;
+
+        let Some(command) = Command::find(version.base10_parse()?, &keyword.to_string()) else {
+            return Err(syn::Error::new(
+                keyword.span(),
+                format!(
+                    "No command '{keyword}' for version {}",
+                    version.base10_digits()
+                ),
+            ));
+        };
+
+        let item: Item = parse2(definition.stream())?;
+        let telety = match cfg {
+            Some(values) => Telety::with_cfg_values(&item, values)?,
+            None => Telety::new(&item)?,
+        };
+
+        let replacement = command.generate_arguments_replacement(&telety, args.stream())?;
+
+        Ok(telety_impl::find_and_replace::find_and_replace(
+            needle,
+            replacement,
+            haystack,
+        ))
+    }
+}
+
+impl Parse for ApplyArgumentsArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // The values of the item's cfg predicates, if it has any
+        let cfg = if input.peek(token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            let mut values = vec![];
+            while !content.is_empty() {
+                values.push(content.parse::<LitBool>()?.value);
+            }
+            Some(values)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            cfg,
+            version: input.parse()?,
+            _comma0: input.parse()?,
+            keyword: input.parse()?,
+            _comma1: input.parse()?,
+            definition: input.parse()?,
+            _comma2: input.parse()?,
+            args: input.parse()?,
+            _comma3: input.parse()?,
+            needle: input.parse()?,
+            _comma4: input.parse()?,
+            haystack: input.parse()?,
+        })
+    }
+}
+
+pub(crate) fn apply_arguments(args: TokenStream) -> syn::Result<TokenStream> {
+    let args: ApplyArgumentsArgs = parse2(args)?;
+    args.apply_arguments()
+}
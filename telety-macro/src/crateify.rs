@@ -7,7 +7,7 @@ pub(crate) fn crateify(arg: TokenStream) -> syn::Result<TokenStream> {
     let mut item: Item = parse2(arg)?;
     directed_visit::visit_mut(
         &mut directed_visit::syn::direct::FullDefault,
-        &mut visitor::Crateify::new(),
+        &mut visitor::Crateify::try_new()?,
         &mut item,
     );
 
@@ -0,0 +1,134 @@
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::{TokenStreamExt as _, quote_spanned};
+use syn::{
+    Ident, LitInt, Path, Token, bracketed,
+    parse::{Parse, ParseStream},
+    parse2,
+    punctuated::Punctuated,
+    spanned::Spanned as _,
+};
+use telety_impl::find_and_replace::SingleToken;
+
+/// `[supported versions], [candidate versions], keyword (args)?, macro_path, needle, [fallback], haystack`  
+/// Items from telety versions without the `versions` command reply to the probe with their path
+/// instead of `[supported versions]`, and only support versions 0 and 1.
+struct DowngradeArgs {
+    supported: Punctuated<LitInt, Token![,]>,
+    candidates: Punctuated<LitInt, Token![,]>,
+    keyword: Ident,
+    args: Option<Group>,
+    macro_path: Path,
+    needle: SingleToken,
+    fallback: Option<Group>,
+    haystack: TokenStream,
+}
+
+impl DowngradeArgs {
+    pub fn downgrade(self) -> syn::Result<TokenStream> {
+        let Self {
+            supported,
+            candidates,
+            keyword,
+            args,
+            macro_path,
+            needle,
+            fallback,
+            haystack,
+        } = self;
+
+        // Candidates are ordered from newest to oldest, so the first supported one is the closest
+        let version = candidates.iter().find(|candidate| {
+            supported
+                .iter()
+                .any(|version| version.base10_digits() == candidate.base10_digits())
+        });
+
+        if let Some(version) = version {
+            Ok(quote_spanned! { macro_path.span() =>
+                #macro_path! { #version, #keyword #args, #needle, #haystack }
+            })
+        } else if let Some(fallback) = fallback {
+            Ok(fallback.stream())
+        } else {
+            let supported = supported
+                .iter()
+                .map(LitInt::base10_digits)
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(syn::Error::new(
+                macro_path.span(),
+                format!(
+                    "No supported version of command '{keyword}' (the item supports versions {supported})"
+                ),
+            ))
+        }
+    }
+}
+
+impl Parse for DowngradeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let supported = if input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            Punctuated::parse_terminated(&content)?
+        } else {
+            // The path of the item, which may start with `$crate`
+            let mut tokens = TokenStream::new();
+            while !input.is_empty() && !input.peek(Token![,]) {
+                tokens.append(input.parse::<TokenTree>()?);
+            }
+            if tokens.is_empty() {
+                return Err(input.error("Expected the supported versions"));
+            }
+            [0, 1]
+                .into_iter()
+                .map(|version| LitInt::new(&version.to_string(), tokens.span()))
+                .collect()
+        };
+        let _comma: Token![,] = input.parse()?;
+
+        let content;
+        bracketed!(content in input);
+        let candidates = Punctuated::parse_terminated(&content)?;
+        let _comma: Token![,] = input.parse()?;
+
+        let keyword = input.parse()?;
+        let args = if input.peek(syn::token::Paren) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let _comma: Token![,] = input.parse()?;
+
+        let macro_path = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+
+        let needle = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+
+        let content;
+        bracketed!(content in input);
+        let fallback = if content.is_empty() {
+            None
+        } else {
+            Some(content.parse()?)
+        };
+        let _comma: Token![,] = input.parse()?;
+
+        Ok(Self {
+            supported,
+            candidates,
+            keyword,
+            args,
+            macro_path,
+            needle,
+            fallback,
+            haystack: input.parse()?,
+        })
+    }
+}
+
+pub(crate) fn downgrade(args: TokenStream) -> syn::Result<TokenStream> {
+    let args: DowngradeArgs = parse2(args)?;
+    args.downgrade()
+}
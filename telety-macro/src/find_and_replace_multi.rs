@@ -0,0 +1,130 @@
+use proc_macro2::{Group, TokenStream};
+use syn::{
+    Ident, LitInt, Token, bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    parse2,
+};
+use telety_impl::find_and_replace::SingleToken;
+
+/// `(version, keyword, [replacement])`
+struct TableEntry {
+    version: LitInt,
+    keyword: Ident,
+    replacement: Group,
+}
+
+impl Parse for TableEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version = content.parse()?;
+        let _comma: Token![,] = content.parse()?;
+        let keyword = content.parse()?;
+        let _comma: Token![,] = content.parse()?;
+        let replacement = content.parse()?;
+
+        Ok(Self {
+            version,
+            keyword,
+            replacement,
+        })
+    }
+}
+
+/// `(version, keyword, needle)`
+struct Request {
+    version: LitInt,
+    keyword: Ident,
+    needle: SingleToken,
+}
+
+impl Parse for Request {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version = content.parse()?;
+        let _comma: Token![,] = content.parse()?;
+        let keyword = content.parse()?;
+        let _comma: Token![,] = content.parse()?;
+        let needle = content.parse()?;
+
+        Ok(Self {
+            version,
+            keyword,
+            needle,
+        })
+    }
+}
+
+struct FindAndReplaceMultiArgs {
+    table: Vec<TableEntry>,
+    requests: Vec<Request>,
+    haystack: TokenStream,
+}
+
+impl FindAndReplaceMultiArgs {
+    pub fn find_and_replace(self) -> syn::Result<TokenStream> {
+        let Self {
+            table,
+            requests,
+            haystack,
+        } = self;
+
+        let mut pairs = vec![];
+        for Request {
+            version,
+            keyword,
+            needle,
+        } in requests
+        {
+            let Some(entry) = table.iter().find(|e| {
+                e.version.base10_digits() == version.base10_digits() && e.keyword == keyword
+            }) else {
+                return Err(syn::Error::new(
+                    keyword.span(),
+                    format!(
+                        "No command '{keyword}' for version {}",
+                        version.base10_digits()
+                    ),
+                ));
+            };
+
+            pairs.push((needle, entry.replacement.stream()));
+        }
+
+        Ok(telety_impl::find_and_replace::find_and_replace_multi(
+            &pairs, haystack,
+        ))
+    }
+}
+
+impl Parse for FindAndReplaceMultiArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let mut table = vec![];
+        while !content.is_empty() {
+            table.push(content.parse()?);
+        }
+        let _comma: Token![,] = input.parse()?;
+
+        let content;
+        bracketed!(content in input);
+        let mut requests = vec![];
+        while !content.is_empty() {
+            requests.push(content.parse()?);
+        }
+        let _comma: Token![,] = input.parse()?;
+
+        Ok(Self {
+            table,
+            requests,
+            haystack: input.parse()?,
+        })
+    }
+}
+
+pub(crate) fn find_and_replace_multi(args: TokenStream) -> syn::Result<TokenStream> {
+    let args: FindAndReplaceMultiArgs = parse2(args)?;
+    args.find_and_replace()
+}
@@ -0,0 +1,53 @@
+use proc_macro2::{Group, TokenStream, TokenTree};
+use syn::{
+    LitInt, Token,
+    parse::{Parse, ParseStream, Parser as _},
+    parse2,
+    punctuated::Punctuated,
+};
+
+/// `versions, { then } { otherwise }`
+struct HandshakeArgs {
+    versions: TokenTree,
+    _comma: Token![,],
+    then: Group,
+    otherwise: Group,
+}
+
+impl Parse for HandshakeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            versions: input.parse()?,
+            _comma: input.parse()?,
+            then: input.parse()?,
+            otherwise: input.parse()?,
+        })
+    }
+}
+
+/// Expands to `then` if `versions` is the output of the `versions` command (e.g. `[0, 1, 2]`),
+/// which means the macro which received the needle was a telety-generated macro.
+/// Otherwise, expands to `otherwise`.
+pub(crate) fn handshake(args: TokenStream) -> syn::Result<TokenStream> {
+    let HandshakeArgs {
+        versions,
+        then,
+        otherwise,
+        ..
+    } = parse2(args)?;
+
+    let is_telety = match versions {
+        TokenTree::Group(group) if group.delimiter() == proc_macro2::Delimiter::Bracket => {
+            Punctuated::<LitInt, Token![,]>::parse_terminated
+                .parse2(group.stream())
+                .is_ok_and(|versions| !versions.is_empty())
+        }
+        _ => false,
+    };
+
+    Ok(if is_telety {
+        then.stream()
+    } else {
+        otherwise.stream()
+    })
+}
@@ -0,0 +1,38 @@
+use proc_macro2::TokenStream;
+use quote::{ToTokens as _, quote};
+use syn::{Path, parse2};
+use telety_impl::Apply;
+
+pub(crate) fn is_telety(args: TokenStream) -> syn::Result<TokenStream> {
+    let path: Path = parse2(args)?;
+
+    // The associated const is provided by the trait, unless the handshake succeeds and the
+    // inherent impl (which takes priority) is generated.
+    let apply = Apply::when_telety(
+        path,
+        quote! {
+            impl __TeletyMarker {
+                #[allow(dead_code)]
+                const IS_TELETY: bool = true;
+            }
+        },
+        TokenStream::new(),
+    );
+
+    Ok(quote! {
+        {
+            struct __TeletyMarker;
+
+            trait __TeletyNotTelety {
+                const IS_TELETY: bool = false;
+            }
+
+            impl __TeletyNotTelety for __TeletyMarker {}
+
+            #apply
+
+            __TeletyMarker::IS_TELETY
+        }
+    }
+    .into_token_stream())
+}
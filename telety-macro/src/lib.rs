@@ -1,15 +1,27 @@
+mod apply_arguments;
 mod crateify;
+mod downgrade;
 mod find_and_replace;
+mod find_and_replace_multi;
+mod handshake;
+mod is_telety;
+mod reflect;
 mod telety;
 mod try_invoke;
+#[cfg(any(feature = "v1", feature = "v2"))]
+mod with;
 
 /// Enable telety for an item.  
 /// The first argument must be the path to the current module (e.g. `#[telety(crate::my_mod)]`).  
+/// Items generated by a `macro_rules!` macro may use `$crate` (e.g. `#[telety($crate::generated)]`),
+/// which refers to the crate defining the `macro_rules!` macro, whichever crate expands it.
+/// `$crate` paths within the item are aliased as usual.
 ///
 /// Optional arguments inlcude:  
 /// * telety_path - Provide a path to the contents of the telety crate.  
 ///   `#[telety(crate::my_mod, telety_path = "::renamed_telety")]`  
-///   By default this is `::telety`, but if you have renamed or re-exported the crate you can specify its location here.
+///   By default this is `::telety`, or the dependency name if telety is renamed in your `Cargo.toml`
+///   (`renamed_telety = { package = "telety", ... }`). If you have re-exported the crate, you can specify its location here.
 /// * macro_ident - The identifier for the telety-generated macro.  
 ///   `#[telety(crate::my_mod, macro_ident = "MyStructImpl")]`  
 ///   Normally, telety generates a macro with the same identifier as the item. But items such as impls do not have identifiers,
@@ -18,6 +30,47 @@ mod try_invoke;
 ///   `#[telety(crate::my_mod, visibility = "pub(crate)")]`  
 ///   telety uses the visibility of the item by default. If the item has no visibility (such as an impl) or you want a more
 ///   restrictive visibility, you can use this argument. The visibility must be equal or more restrictive than the item's visibility.
+/// * extensions - A comma-separated list of extension macros which provide additional commands.  
+///   `#[telety(crate::my_mod, extensions = "::my_ext_crate::my_ext")]`  
+///   Commands created with `Command::extension` are forwarded to the extension macro with the same identifier,
+///   along with the item definition. A leading `crate` refers to the crate defining the item.
+/// * helper_attrs - A comma-separated list of attributes for other macros to read from the item definition.  
+///   `#[telety(crate::my_mod, helper_attrs = "mix, builder")]`  
+///   These attributes are removed from the item, its fields and its variants, so the compiler does not reject them,
+///   but they are kept in the definition provided by the telety-generated macro.
+/// * export - Which parts of the item are included in its definition, `"all"` (the default) or `"public"`.  
+///   `#[telety(crate::my_mod, export = "public")]`  
+///   With `"public"`, fields which are not `pub` or `pub(crate)` are removed from the definition (the types of removed
+///   tuple struct fields are replaced with `()`) and the item is marked `#[non_exhaustive]`. Default trait method bodies
+///   are replaced with `;`. Only the types in the definition are aliased, so private types do not need to be aliasable.
+/// * proxy - The path to an item which the attributed definition describes.  
+///   `#[telety(crate::my_mod, proxy = "::other_crate::OtherStruct")]`  
+///   The proxied item is re-exported in place of the definition, along with the telety-generated macro.
+///   Fields which are not public may be omitted from struct definitions.
+///   For structs and enums, a compile error is reported if the generic parameters, fields or variants do not match the proxied item.
+///
+/// Inner `#[telety(...)]` attributes on fields, variants, associated items, generic parameters and function arguments control
+/// how the types within them are aliased:
+/// * `#[telety(skip)]` - Do not alias any types, e.g. private helper types.
+/// * `#[telety(alias)]` - Alias all types, including traits regardless of `alias_traits`.
+///   Types which cannot be aliased are reported as errors.
+/// * `#[telety(builtin)]` - The outermost types are available everywhere (e.g. from the prelude), so are not aliased.
+///   Their generic arguments are aliased as usual.
+///
+/// ```rust,ignore
+/// #[telety(crate::my_mod)]
+/// pub struct MyStruct {
+///     #[telety(skip)]
+///     helper: PrivateHelper,
+///     #[telety(builtin)]
+///     pub values: Vec<MyValue>,
+/// }
+/// ```
+/// These attributes are removed from the item, but kept in the definition provided by the telety-generated macro.
+///
+/// Aliases of types used in fields, variants and associated items with `#[cfg(...)]` attributes are only
+/// generated when one of their uses is enabled. The definition keeps these `#[cfg(...)]`s, but `v2::TY` can
+/// provide the definition with them resolved in the item's crate (`ty(cfg = resolved)`).
 #[proc_macro_attribute]
 pub fn telety(
     attr_arg: proc_macro::TokenStream,
@@ -28,6 +81,58 @@ pub fn telety(
     ts.into()
 }
 
+/// Generate a telety proc macro from a single function.  
+/// The function receives a `Telety` for each path passed to the macro, and the tokens following the paths:
+/// ```rust,ignore
+/// /// mix!(path_to_struct0, path_to_struct1; new_struct_ident)
+/// #[telety::reflect]
+/// fn mix(items: &[Telety], args: TokenStream) -> syn::Result<TokenStream> {
+///     // ...
+/// }
+/// ```
+/// Two proc macros are generated, `mix` and the hidden second stage `mix_impl`.
+/// See `telety::driver` for details.
+///
+/// Optional arguments include:
+/// * second_stage - The path to the second stage macro, as seen from the crates using the proc macro.  
+///   `#[telety::reflect(second_stage = "::my_facade::mix_impl")]`  
+///   By default this is `::this_crate::name_impl`. If the proc macro is re-exported through another crate,
+///   specify the re-exported path here.
+/// * telety_path - Provide a path to the contents of the telety crate, as seen from the proc macro crate.  
+///   `#[telety::reflect(telety_path = "::renamed_telety")]`  
+///   By default this is `::telety`, or the dependency name if telety is renamed in the proc macro crate's `Cargo.toml`.
+#[proc_macro_attribute]
+pub fn reflect(
+    attr_arg: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) =
+        reflect::reflect_impl(attr_arg.into(), item.into()).map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[cfg(feature = "v1")]
+#[proc_macro]
+pub fn deep_step(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) =
+        telety_impl::deep::step(arg.into()).map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[proc_macro]
+pub fn downgrade(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) =
+        downgrade::downgrade(arg.into()).map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[proc_macro]
+pub fn apply_arguments(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) =
+        apply_arguments::apply_arguments(arg.into()).map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
 #[proc_macro]
 pub fn crateify(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let (Ok(ts) | Err(ts)) = crateify::crateify(arg.into()).map_err(syn::Error::into_compile_error);
@@ -41,9 +146,54 @@ pub fn find_and_replace(arg: proc_macro::TokenStream) -> proc_macro::TokenStream
     ts.into()
 }
 
+#[proc_macro]
+pub fn find_and_replace_multi(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = find_and_replace_multi::find_and_replace_multi(arg.into())
+        .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[proc_macro]
+pub fn handshake(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) =
+        handshake::handshake(arg.into()).map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[proc_macro]
+pub fn is_telety(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) =
+        is_telety::is_telety(arg.into()).map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
 #[proc_macro]
 pub fn try_invoke(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let (Ok(ts) | Err(ts)) =
         try_invoke::try_invoke_impl(arg.into()).map_err(syn::Error::into_compile_error);
     ts.into()
 }
+
+#[cfg(feature = "v1")]
+#[proc_macro]
+pub fn with_ty(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = with::with(&telety_impl::version::v1::TY, true, arg.into())
+        .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[cfg(feature = "v2")]
+#[proc_macro]
+pub fn with_fields(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = with::with(&telety_impl::version::v2::FIELDS, false, arg.into())
+        .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+#[cfg(feature = "v2")]
+#[proc_macro]
+pub fn with_variants(arg: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = with::with(&telety_impl::version::v2::VARIANTS, false, arg.into())
+        .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
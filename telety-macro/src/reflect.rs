@@ -0,0 +1,119 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote_spanned};
+use syn::{
+    Expr, ExprLit, ItemFn, Lit, MetaNameValue, Path, Token, Visibility, parse_quote, parse2,
+    punctuated::Punctuated, spanned::Spanned as _,
+};
+use telety_impl::manifest;
+
+struct ReflectOptions {
+    second_stage: Option<Path>,
+    telety_path: Option<Path>,
+}
+
+impl ReflectOptions {
+    fn parse(attr_args: TokenStream) -> syn::Result<Self> {
+        let mut second_stage = None;
+        let mut telety_path = None;
+
+        let named_args = syn::parse::Parser::parse2(
+            Punctuated::<MetaNameValue, Token![,]>::parse_terminated,
+            attr_args,
+        )?;
+        for named_arg in named_args {
+            let Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) = &named_arg.value
+            else {
+                return Err(syn::Error::new(
+                    named_arg.value.span(),
+                    "Expected a string literal",
+                ));
+            };
+
+            if named_arg.path.is_ident("second_stage") {
+                second_stage = Some(value.parse()?);
+            } else if named_arg.path.is_ident("telety_path") {
+                telety_path = Some(value.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    named_arg.path.span(),
+                    "Invalid parameter name",
+                ));
+            }
+        }
+
+        Ok(Self {
+            second_stage,
+            telety_path,
+        })
+    }
+}
+
+pub(crate) fn reflect_impl(attr_args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let ReflectOptions {
+        second_stage,
+        telety_path,
+    } = ReflectOptions::parse(attr_args)?;
+    let mut item_fn: ItemFn = parse2(item)?;
+
+    // Documentation belongs on the first stage, which is the public interface
+    let (doc_attrs, attrs): (Vec<_>, Vec<_>) = item_fn
+        .attrs
+        .drain(..)
+        .partition(|attr| attr.path().is_ident("doc"));
+    item_fn.attrs = attrs;
+    item_fn.vis = Visibility::Inherited;
+
+    let ident = &item_fn.sig.ident;
+    let second_stage_ident = format_ident!("{ident}_impl");
+
+    let second_stage = match second_stage {
+        Some(second_stage) => second_stage,
+        None => {
+            let crate_name = manifest::crate_name()
+                .and_then(|crate_name| {
+                    crate_name.ok_or_else(|| {
+                        syn::Error::new(
+                            Span::call_site(),
+                            "The calling crate cannot be referred to by name in doctests",
+                        )
+                    })
+                })
+                .map_err(|error| {
+                    syn::Error::new(
+                        Span::call_site(),
+                        format!("{error}, provide a 'second_stage' argument"),
+                    )
+                })?;
+            parse_quote!(::#crate_name::#second_stage_ident)
+        }
+    };
+    let telety_path = telety_path.unwrap_or_else(manifest::telety_path);
+
+    let span = item_fn.sig.span();
+
+    Ok(quote_spanned! { span =>
+        #(#doc_attrs)*
+        #[proc_macro]
+        pub fn #ident(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+            let (Ok(ts) | Err(ts)) = #telety_path::driver::FirstStage::new(
+                #telety_path::__private::syn::parse_quote!(#second_stage)
+            )
+            .expand(input.into())
+            .map_err(#telety_path::__private::syn::Error::into_compile_error);
+            ts.into()
+        }
+
+        #[doc(hidden)]
+        #[proc_macro]
+        pub fn #second_stage_ident(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+            #item_fn
+
+            let (Ok(ts) | Err(ts)) = #telety_path::driver::second_stage(input.into(), #ident)
+                .map_err(#telety_path::__private::syn::Error::into_compile_error);
+            ts.into()
+        }
+    })
+}
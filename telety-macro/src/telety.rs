@@ -1,19 +1,17 @@
-use std::borrow::Cow;
-
-use proc_macro2::{Span, TokenStream};
-use quote::{TokenStreamExt as _, format_ident, quote_spanned};
+use proc_macro2::{Group, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::{ToTokens as _, TokenStreamExt as _, format_ident, quote, quote_spanned};
 use syn::{
-    Attribute, Ident, Item, LitInt, Visibility, parse_quote, parse_quote_spanned, parse2,
-    spanned::Spanned as _,
+    Attribute, Fields, Ident, Item, LitInt, Member, Path, Visibility, parse_quote,
+    parse_quote_spanned, parse2, spanned::Spanned as _,
 };
-use telety_impl::{Options, Telety, version, visitor};
+use telety_impl::{Command, Options, Telety, version, visitor};
 
 pub(crate) fn telety_impl(attr_args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
     let item: Item = parse2(item)?;
     let mut options: Options = parse2(attr_args)?;
     directed_visit::visit_mut(
         &mut directed_visit::syn::direct::FullDefault,
-        &mut visitor::Decrateify::new(),
+        &mut visitor::Decrateify::try_new()?,
         &mut options.module_path,
     );
 
@@ -35,13 +33,17 @@ fn generate_output(telety: &Telety) -> syn::Result<TokenStream> {
     let macro_ident = telety.macro_ident();
 
     let item = if let Some(proxy) = &telety.options().proxy {
-        Cow::Owned(parse_quote! {
+        let proxy_check = generate_proxy_check(&telety.stripped_item(), proxy);
+        let item: Item = parse_quote! {
             #vis use #proxy::{self as #textual_ident};
-        })
+        };
+        quote!(#item #proxy_check)
     } else {
-        Cow::Borrowed(telety.item())
+        telety.stripped_item().to_token_stream()
     };
 
+    let cfg_macros = generate_cfg_macros(telety);
+
     let span = Span::call_site();
 
     let map_module = map.with_module();
@@ -51,6 +53,8 @@ fn generate_output(telety: &Telety) -> syn::Result<TokenStream> {
 
         #item_macro
 
+        #cfg_macros
+
         #[doc(hidden)]
         #vis use #textual_ident as #macro_ident;
 
@@ -58,6 +62,127 @@ fn generate_output(telety: &Telety) -> syn::Result<TokenStream> {
     })
 }
 
+/// Generates a macro for each of the item's cfg predicates, which appends the value of the predicate
+/// (evaluated in this crate) to its input, and passes it on to the next macro.
+fn generate_cfg_macros(telety: &Telety) -> TokenStream {
+    let vis = telety.visibility();
+    let macro_export = macro_export(telety);
+
+    let mut macros = TokenStream::new();
+    for (index, predicate) in telety.cfg_predicates().iter().enumerate() {
+        let ident = telety.cfg_macro_ident(index);
+        let reexport_ident = telety.cfg_macro_reexport_ident(index);
+
+        for (cfg, value) in [
+            (quote!(#predicate), quote!(true)),
+            (quote!(not(#predicate)), quote!(false)),
+        ] {
+            macros.append_all(quote! {
+                #[cfg(#cfg)]
+                #[doc(hidden)]
+                #macro_export
+                macro_rules! #ident {
+                    ([$($values:tt)*] [$($next:tt)*] $($rest:tt)*) => {
+                        $($next)*! { [$($values)* #value] $($rest)* }
+                    };
+                }
+            });
+        }
+
+        macros.append_all(quote! {
+            #[doc(hidden)]
+            #vis use #ident as #reexport_ident;
+        });
+    }
+
+    macros
+}
+
+/// Checks that the proxied item has the generic parameters, fields and variants of the definition,
+/// so the definition cannot silently become stale.  
+/// The patterns are exhaustive, so omitted fields and variants (and any generic parameters only they use)
+/// are errors. Structs, variants and enums marked `#[non_exhaustive]` in the definition may omit them,
+/// which is required for private fields and foreign `#[non_exhaustive]` items.
+fn generate_proxy_check(item: &Item, proxy: &Path) -> Option<TokenStream> {
+    let rest = |attrs: &[Attribute]| {
+        attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("non_exhaustive"))
+            .then(|| quote!(..))
+    };
+
+    let (generics, pattern) = match item {
+        Item::Struct(item_struct) => {
+            let (fields, checks) = proxy_check_fields(&item_struct.fields);
+            let rest = rest(&item_struct.attrs);
+            (
+                &item_struct.generics,
+                quote!(#proxy { #fields #rest } => { #checks }),
+            )
+        }
+        Item::Enum(item_enum) => {
+            let variants = item_enum.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let (fields, checks) = proxy_check_fields(&variant.fields);
+                let rest = rest(&variant.attrs);
+                quote!(#proxy::#ident { #fields #rest } => { #checks })
+            });
+            // Unreachable if the proxied enum is in this crate
+            let wildcard = rest(&item_enum.attrs).map(|_| {
+                quote! {
+                    #[allow(unreachable_patterns)]
+                    _ => {}
+                }
+            });
+            (&item_enum.generics, quote!(#(#variants)* #wildcard))
+        }
+        _ => return None,
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let span = proxy.span();
+
+    Some(quote_spanned! { span =>
+        const _: () = {
+            #[allow(dead_code)]
+            fn __telety_proxy_check #impl_generics (value: #proxy #ty_generics) #where_clause {
+                // Avoids coercions, so the field types must match exactly
+                fn type_of<T: ?::core::marker::Sized>(_: &T) -> ::core::marker::PhantomData<T> {
+                    ::core::marker::PhantomData
+                }
+
+                match &value {
+                    #pattern
+                }
+            }
+        };
+    })
+}
+
+/// The field patterns binding each field of `fields`, and the checks of their types
+fn proxy_check_fields(fields: &Fields) -> (TokenStream, TokenStream) {
+    let mut patterns = TokenStream::new();
+    let mut checks = TokenStream::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = field
+            .ident
+            .clone()
+            .map(Member::Named)
+            .unwrap_or_else(|| Member::Unnamed(index.into()));
+        let binding = format_ident!("__telety_field_{index}");
+        let ty = &field.ty;
+
+        patterns.append_all(quote!(#member: #binding,));
+        // Bind before annotating, so the annotation cannot cause a coercion
+        checks.append_all(quote_spanned! { ty.span() =>
+            let #binding = type_of(#binding);
+            let _: ::core::marker::PhantomData<#ty> = #binding;
+        });
+    }
+
+    (patterns, checks)
+}
+
 pub(crate) fn macro_export(telety: &Telety) -> Option<Attribute> {
     match telety.visibility() {
         Visibility::Public(vis_pub) => {
@@ -72,18 +197,32 @@ pub(crate) fn macro_export(telety: &Telety) -> Option<Attribute> {
 pub(crate) fn generate_macro(telety: &Telety, ident: &Ident) -> syn::Result<TokenStream> {
     let span = telety.item().span();
 
-    let mut arms = TokenStream::new();
-    for &(version, commands) in version::VERSIONS {
+    let mut arms = Command::generate_multi_macro_arm(telety)?;
+    arms.append_all(Command::generate_downgrade_macro_arm(telety));
+    arms.append_all(Command::generate_extension_macro_arms(telety)?);
+    if cfg!(feature = "full-errors") {
+        arms.append_all(quote_spanned! { span =>
+            (ext, $extension:ident $($tokens:tt)*) => {
+                compile_error!(concat!("No extension '", stringify!($extension), "'"));
+            };
+        });
+    }
+    for &(_version, commands) in version::VERSIONS {
         for command in commands {
             let arm = command.generate_macro_arm(telety)?;
             arms.append_all(arm);
         }
-
+    }
+    arms.append_all(Command::generate_arguments_macro_arm(telety));
+    for &(version, commands) in version::VERSIONS {
         let version = LitInt::new(&version.to_string(), span);
 
         if cfg!(feature = "full-errors") {
+            for command in commands {
+                arms.append_all(command.generate_error_macro_arms(telety)?);
+            }
             arms.append_all(quote_spanned! { span =>
-                (#version $command:ident $($tokens:tt)*) => {
+                (#version, $command:ident $($tokens:tt)*) => {
                     compile_error!(concat!("No command '",  stringify!($command), "' for version ", stringify!(#version)));
                 };
                 (#version $($tokens:tt)*) => {
@@ -105,6 +244,7 @@ pub(crate) fn generate_macro(telety: &Telety, ident: &Ident) -> syn::Result<Toke
     }
 
     let macro_export = macro_export(telety);
+    let arms = dollar_crate(arms);
 
     Ok(quote_spanned! { span =>
         #[doc(hidden)]
@@ -114,3 +254,36 @@ pub(crate) fn generate_macro(telety: &Telety, ident: &Ident) -> syn::Result<Toke
         }
     })
 }
+
+/// Paths beginning with `crate` (in the item definition and alias paths) would refer to the invoking crate
+/// once the output leaves the telety-generated macro. Replace it with `$crate` so they still refer to the item's crate.
+/// `crate` in visibilities (`pub(crate)`, `pub(in crate::my_mod)`) is left as is.
+fn dollar_crate(tokens: TokenStream) -> TokenStream {
+    let mut output = TokenStream::new();
+    let mut previous: Option<TokenTree> = None;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match &token {
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(group.delimiter(), dollar_crate(group.stream()));
+                replaced.set_span(group.span());
+                output.append(replaced);
+            }
+            TokenTree::Ident(ident)
+                if ident == "crate"
+                    && matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ':')
+                    && !matches!(&previous, Some(TokenTree::Ident(previous)) if previous == "in")
+                    && !matches!(&previous, Some(TokenTree::Punct(previous)) if previous.as_char() == '$') =>
+            {
+                let mut dollar = Punct::new('$', Spacing::Alone);
+                dollar.set_span(ident.span());
+                output.append(dollar);
+                output.append(ident.clone());
+            }
+            _ => output.append(token.clone()),
+        }
+        previous = Some(token);
+    }
+
+    output
+}
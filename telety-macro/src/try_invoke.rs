@@ -1,24 +1,41 @@
-use std::mem;
-
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-    Macro, Token,
+    Ident, Macro, MacroDelimiter, Token,
     parse::{Parse, ParseStream},
     parse_quote, parse2,
 };
 
 struct TryInvokeArgs {
-    maybe_macro: Macro,
-    semicolon: Option<Token![;]>,
+    expression: bool,
+    candidates: Vec<Macro>,
     fallback_tokens: TokenStream,
 }
 
 impl Parse for TryInvokeArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `expr =>` selects expression position
+        let expression = input
+            .fork()
+            .parse::<Ident>()
+            .is_ok_and(|ident| ident == "expr")
+            && input.peek2(Token![=>]);
+        if expression {
+            let _expr: Ident = input.parse()?;
+            let _arrow: Token![=>] = input.parse()?;
+        }
+
+        let mut candidates = vec![input.parse()?];
+        while input.parse::<Option<Token![else]>>()?.is_some() {
+            candidates.push(input.parse()?);
+        }
+
+        // Separates the candidates from the fallback tokens
+        let _semicolon: Option<Token![;]> = input.parse()?;
+
         Ok(Self {
-            maybe_macro: input.parse()?,
-            semicolon: input.parse()?,
+            expression,
+            candidates,
             fallback_tokens: input.parse()?,
         })
     }
@@ -26,28 +43,74 @@ impl Parse for TryInvokeArgs {
 
 pub(crate) fn try_invoke_impl(arg: TokenStream) -> syn::Result<TokenStream> {
     let TryInvokeArgs {
-        mut maybe_macro,
-        semicolon,
+        expression,
+        candidates,
         fallback_tokens,
     } = parse2(arg)?;
 
-    let maybe_macro_path = mem::replace(&mut maybe_macro.path, parse_quote!(__macro_fallback));
+    // Blocks have their own scope for imports, like anonymous consts
+    let scope = |contents: TokenStream| {
+        if expression {
+            quote!({ #contents })
+        } else {
+            quote!(const _: () = { #contents };)
+        }
+    };
+
+    let fallback_ident = |index: usize| {
+        if index == 0 {
+            format_ident!("__macro_fallback")
+        } else {
+            format_ident!("__macro_fallback_{index}")
+        }
+    };
+
+    // Invokes the candidate through the name its path may be imported as
+    let invoke = |index: usize, candidate: &Macro| {
+        let mut candidate = candidate.clone();
+        candidate.path = {
+            let ident = fallback_ident(index);
+            parse_quote!(#ident)
+        };
+        let semicolon = (!expression && !matches!(candidate.delimiter, MacroDelimiter::Brace(_)))
+            .then(<Token![;]>::default);
+        quote!(#candidate #semicolon)
+    };
+
+    // Each candidate is imported over the name which, if the candidate is not a macro,
+    // refers to an adapter invoking the next candidate (or the fallback tokens).
+    // Build from the innermost scope (the first candidate) out.
+    let last = candidates.len() - 1;
+    let mut output = invoke(0, &candidates[0]);
+    for (index, candidate) in candidates.iter().enumerate() {
+        let ident = fallback_ident(index);
+        let path = &candidate.path;
+
+        let adapter_ident = format_ident!("{ident}_adapter");
+        let adapter_contents = if index == last {
+            fallback_tokens.clone()
+        } else {
+            invoke(index + 1, &candidates[index + 1])
+        };
 
-    Ok(quote! {
-        const _: () = {
-            macro_rules! __macro_fallback_adapter {
+        let imported = scope(quote! {
+            #[allow(unused_imports)]
+            use #path as #ident;
+            #output
+        });
+
+        output = quote! {
+            macro_rules! #adapter_ident {
                 ($($tokens:tt)*) => {
-                    #fallback_tokens
+                    #adapter_contents
                 };
             }
 
             #[allow(unused_imports)]
-            use __macro_fallback_adapter as __macro_fallback;
-            const _: () = {
-                #[allow(unused_imports)]
-                use #maybe_macro_path as __macro_fallback;
-                #maybe_macro #semicolon
-            };
+            use #adapter_ident as #ident;
+            #imported
         };
-    })
+    }
+
+    Ok(scope(output))
 }
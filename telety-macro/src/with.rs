@@ -0,0 +1,60 @@
+use proc_macro2::{Group, TokenStream};
+use quote::{ToTokens as _, format_ident, quote};
+use syn::{
+    Path, Token,
+    parse::{Parse, ParseStream},
+    parse2,
+};
+use telety_impl::Command;
+
+/// `path => callback! { extra }`
+struct WithArgs {
+    path: Path,
+    _arrow: Token![=>],
+    callback: Path,
+    _bang: Token![!],
+    extra: Group,
+}
+
+impl Parse for WithArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            path: input.parse()?,
+            _arrow: input.parse()?,
+            callback: input.parse()?,
+            _bang: input.parse()?,
+            extra: input.parse()?,
+        })
+    }
+}
+
+/// Applies `command` to the item at `path`, then invokes `callback` with the output of `command`
+/// as its first token tree, followed by the `extra` tokens.
+/// If `bracket` is set, the output is wrapped in `[...]` so it is a single token tree.
+pub(crate) fn with(
+    command: &'static Command,
+    bracket: bool,
+    args: TokenStream,
+) -> syn::Result<TokenStream> {
+    let WithArgs {
+        path,
+        callback,
+        extra,
+        ..
+    } = parse2(args)?;
+
+    let needle = format_ident!("__telety_with_needle");
+    let output = if bracket {
+        quote!([#needle])
+    } else {
+        needle.to_token_stream()
+    };
+
+    let extra_tokens = extra.stream();
+    let mut arguments = Group::new(extra.delimiter(), quote!(#output #extra_tokens));
+    arguments.set_span(extra.span());
+
+    let haystack = quote!(#callback! #arguments);
+
+    Ok(command.apply(path, needle, haystack).into_token_stream())
+}
@@ -0,0 +1,344 @@
+//! Proc macros using telety, invoked by the telety tests.
+
+use proc_macro2::{Group, TokenStream};
+use quote::{ToTokens as _, format_ident, quote};
+use syn::{
+    Ident, Item, LitInt, Path, PathArguments, Token, Type,
+    parse::{ParseStream, Parser as _},
+    parse_quote, parse2,
+};
+use telety::{
+    Apply, Batch, Command, Telety,
+    deep::{Children, Deep, Node},
+    v0, v1,
+};
+
+/// `describe!(path0, path1, ...; IDENT)`  
+/// Defines `const IDENT: &[&str]`, with the path and field names of each struct.
+#[telety::reflect]
+fn describe(items: &[Telety], args: TokenStream) -> syn::Result<TokenStream> {
+    let ident: Ident = parse2(args)?;
+
+    let descriptions = items.iter().map(|item| {
+        let fields: Vec<_> = match item.item() {
+            Item::Struct(item_struct) => item_struct
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref().map(Ident::to_string))
+                .collect(),
+            _ => vec![],
+        };
+        format!(
+            "{} {{ {} }}",
+            item.path().into_token_stream(),
+            fields.join(" ")
+        )
+    });
+
+    Ok(quote! {
+        const #ident: &[&str] = &[#(#descriptions),*];
+    })
+}
+
+/// `batch!(IDENT, forward = unique_ident | auto; path0, path1 else { fallback }, ...)`  
+/// Applies [v0::PATH] to each path with a [Batch], forwarding the output through macros.
+/// Defines `const IDENT: &[&str]`, with the stringified path (or fallback) of each entry.
+#[proc_macro]
+pub fn batch(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let ident: Ident = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let forward: Ident = input.parse()?;
+        if forward != "forward" {
+            return Err(syn::Error::new(forward.span(), "Expected 'forward'"));
+        }
+        let _eq: Token![=] = input.parse()?;
+        let unique_ident: Ident = input.parse()?;
+        let _semi: Token![;] = input.parse()?;
+
+        let mut batch = Batch::new();
+        batch = if unique_ident == "auto" {
+            batch.with_auto_macro_forwarding()
+        } else {
+            batch.with_macro_forwarding(unique_ident)
+        };
+        while !input.is_empty() {
+            let path: Path = input.parse()?;
+            batch = if input.parse::<Option<Token![else]>>()?.is_some() {
+                let fallback: Group = input.parse()?;
+                batch.add_with_fallback(path, &v0::PATH, fallback.stream())
+            } else {
+                batch.add(path, &v0::PATH)
+            };
+            let _comma: Option<Token![,]> = input.parse()?;
+        }
+
+        Ok(batch.invoke(|needles| {
+            quote! {
+                pub const #ident: &[&str] = &[#(stringify!(#needles)),*];
+            }
+        }))
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `path_or!(macro_path, fallback)`  
+/// Expands in expression position to the stringified path of the telety item at `macro_path`, or `fallback`.
+#[proc_macro]
+pub fn path_or(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let macro_path: Path = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let fallback: TokenStream = input.parse()?;
+
+        let needle = format_ident!("__needle__");
+        Ok(v0::PATH
+            .apply(macro_path, needle.clone(), quote!(stringify!(#needle)))
+            .with_fallback(fallback)
+            .in_expression()
+            .into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `path_const!(IDENT, macro_path)`  
+/// Defines `const IDENT: &str`, with the stringified path of the telety item at `macro_path`, or `"none"`.
+/// The output is forwarded with [Apply::with_auto_macro_forwarding](telety::Apply::with_auto_macro_forwarding).
+#[proc_macro]
+pub fn path_const(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let ident: Ident = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let macro_path: Path = input.parse()?;
+
+        let needle = format_ident!("__needle__");
+        Ok(v0::PATH
+            .apply(
+                macro_path,
+                needle.clone(),
+                quote!(pub const #ident: &str = stringify!(#needle);),
+            )
+            .with_fallback(quote!(pub const #ident: &str = "none";))
+            .with_auto_macro_forwarding()
+            .into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `std_path_or!(Type, fallback)` or `std_path_or!(Type as std_path, fallback)`  
+/// Like [path_or], with [Apply::with_std_proxy_fallback](telety::Apply::with_std_proxy_fallback),
+/// or [Apply::with_std_proxy_fallback_for](telety::Apply::with_std_proxy_fallback_for) given `std_path`.
+/// Generic arguments of the type are ignored.
+#[proc_macro]
+pub fn std_path_or(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let mut ty: Type = input.parse()?;
+        // Types forwarded by `macro_rules!` macros are wrapped in a group
+        let ty = loop {
+            match ty {
+                Type::Group(group) => ty = *group.elem,
+                ty => break ty,
+            }
+        };
+        let Type::Path(type_path) = ty else {
+            return Err(input.error("Expected a type path"));
+        };
+        let mut macro_path = type_path.path;
+        if let Some(last) = macro_path.segments.last_mut() {
+            last.arguments = PathArguments::None;
+        }
+        let std_path: Option<Path> = if input.parse::<Option<Token![as]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let _comma: Token![,] = input.parse()?;
+        let fallback: TokenStream = input.parse()?;
+
+        let needle = format_ident!("__needle__");
+        let apply = v0::PATH
+            .apply(macro_path, needle.clone(), quote!(stringify!(#needle)))
+            .with_fallback(fallback)
+            .in_expression();
+        let apply = match std_path {
+            Some(std_path) => apply.with_std_proxy_fallback_for(std_path),
+            None => apply.with_std_proxy_fallback(),
+        };
+        Ok(apply.into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `multi_or!(macro_path, fallback)`  
+/// Applies [v0::PATH] and [v1::UNIQUE_IDENT] with [Apply::multi], expanding in expression position
+/// to the stringified path and unique identifier of the telety item at `macro_path`, or `fallback`.
+#[proc_macro]
+pub fn multi_or(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let macro_path: Path = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let fallback: TokenStream = input.parse()?;
+
+        let path_needle = format_ident!("__path__");
+        let ident_needle = format_ident!("__ident__");
+        Ok(Apply::multi(
+            macro_path,
+            [
+                (&v0::PATH, path_needle.clone()),
+                (&v1::UNIQUE_IDENT, ident_needle.clone()),
+            ],
+            quote!(stringify!(#path_needle #ident_needle)),
+        )
+        .with_fallback(fallback)
+        .in_expression()
+        .into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `multi_const!(IDENT, macro_path)`  
+/// Like [multi_or], but defines `const IDENT: &str`, or `"none"`, in item position.
+/// The output is forwarded with [Apply::with_auto_macro_forwarding](telety::Apply::with_auto_macro_forwarding).
+#[proc_macro]
+pub fn multi_const(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let ident: Ident = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let macro_path: Path = input.parse()?;
+
+        let path_needle = format_ident!("__path__");
+        let ident_needle = format_ident!("__ident__");
+        Ok(Apply::multi(
+            macro_path,
+            [
+                (&v0::PATH, path_needle.clone()),
+                (&v1::UNIQUE_IDENT, ident_needle.clone()),
+            ],
+            quote!(pub const #ident: &str = stringify!(#path_needle #ident_needle);),
+        )
+        .with_fallback(quote!(pub const #ident: &str = "none";))
+        .with_auto_macro_forwarding()
+        .into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `select_type!(macro_path, Then, Otherwise)`  
+/// Expands in type position to `Then` if `macro_path` is a telety item, or `Otherwise`.
+#[proc_macro]
+pub fn select_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let macro_path: Path = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let then: Type = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let otherwise: Type = input.parse()?;
+
+        Ok(Apply::when_telety(macro_path, then, otherwise)
+            .in_type()
+            .into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `downgrade!(version keyword, macro_path, needle, haystack)`  
+/// Applies the [Command] with [Apply::with_downgrade](telety::Apply::with_downgrade).
+#[proc_macro]
+pub fn downgrade(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let version: LitInt = input.parse()?;
+        let keyword: Ident = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let macro_path: Path = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let needle: Ident = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let haystack: TokenStream = input.parse()?;
+
+        let command = Command::find(version.base10_parse()?, &keyword.to_string())
+            .ok_or_else(|| syn::Error::new(keyword.span(), "Unknown command"))?;
+
+        Ok(command
+            .apply(macro_path, needle, haystack)
+            .with_downgrade()
+            .into_token_stream())
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// `deep!(root, max_depth; Marker)`  
+/// Reads the tree of definitions below `root` with [Deep::expand], then implements `Tree` for `Marker`
+/// with a description of the tree (see [deep_impl]).
+#[proc_macro]
+pub fn deep(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (Ok(ts) | Err(ts)) = (|input: ParseStream| {
+        let root: Path = input.parse()?;
+        let _comma: Token![,] = input.parse()?;
+        let max_depth: LitInt = input.parse()?;
+        let _semi: Token![;] = input.parse()?;
+        let marker: Ident = input.parse()?;
+
+        Ok(Deep::new(parse_quote!(::telety_test_macros::deep_impl))
+            .with_max_depth(max_depth.base10_parse()?)
+            .expand(root, marker))
+    })
+    .parse(input)
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
+
+/// The continuation of [deep]. Each node is described by the identifier of its telety macro
+/// (or `_` without telety), followed by `{ children }`, `cycle` or `..`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn deep_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    fn describe(node: &Node) -> syn::Result<String> {
+        let Some(telety) = node.telety() else {
+            return Ok("_".to_string());
+        };
+        let ident = telety?.macro_ident().to_string();
+
+        Ok(match node.children() {
+            Children::Expanded(nodes) => {
+                let children = nodes
+                    .iter()
+                    .map(describe)
+                    .collect::<syn::Result<Vec<_>>>()?;
+                format!("{ident} {{ {} }}", children.join(", "))
+            }
+            Children::Cycle => format!("{ident} cycle"),
+            Children::DepthLimit => format!("{ident} .."),
+            Children::NotTelety => unreachable!("Nodes without telety have no definition"),
+        })
+    }
+
+    let (Ok(ts) | Err(ts)) = telety::deep::second_stage(input.into(), |root, args| {
+        let marker: Ident = parse2(args)?;
+        let tree = describe(root)?;
+
+        // Impls are visible outside of the anonymous blocks the tree is read in
+        Ok(quote! {
+            impl Tree for #marker {
+                const TREE: &str = #tree;
+            }
+        })
+    })
+    .map_err(syn::Error::into_compile_error);
+    ts.into()
+}
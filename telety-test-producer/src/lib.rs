@@ -0,0 +1,45 @@
+//! Items with telety, read by the telety tests from another crate.
+
+use telety::telety;
+
+pub mod shapes {
+    use telety::telety;
+
+    #[telety(crate::shapes)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+}
+
+#[telety(crate)]
+pub struct Labeled<T> {
+    pub label: String,
+    pub point: crate::shapes::Point,
+    pub value: T,
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use telety::telety;
+}
+
+/// Generates a telety item using this crate's types from within a `macro_rules!` macro.
+/// Without a module path, the item is generated in [generated], using `$crate` in its module path.
+#[macro_export]
+macro_rules! generate {
+    ($name:ident) => {
+        $crate::generate!($name in $crate::generated);
+    };
+    ($name:ident in $($module:tt)*) => {
+        #[$crate::__private::telety($($module)*)]
+        pub struct $name(
+            pub $crate::shapes::Point,
+            pub ::core::option::Option<$crate::shapes::Point>,
+        );
+    };
+}
+
+pub mod generated {
+    crate::generate!(Generated);
+}
@@ -13,7 +13,7 @@
 //! ```
 //! If the item has other attributes, [`#[telety]`](telety) should be placed after the last attribute which modifies the item definition.
 //! # Using telety information
-//! The v* (e.g. [v0], [v1]) modules contain objects for generating the TokenStreams to read telety information.  
+//! The v* (e.g. [v0], [v1], [v2]) modules contain objects for generating the TokenStreams to read telety information.  
 //! You will need two macros (or one that has two modes), one to generate the code to read the information,
 //! and a second to use the information for your own purposes.  
 //! The process is a bit cumbersome, but works like this:
@@ -102,19 +102,20 @@
 //!     let telety1 = Telety::new(&item1);
 //!     // Get the fields from the struct definitions
 //!     // ...
+//!     // Switches to `crate::...` if in the same crate the alias was defined,
+//!     // otherwise keeps the path as `::my_crate::...`.
+//!     let mut crateify = telety::visitor::Crateify::try_new()?;
 //!     // Change the original type tokens to our aliases
 //!     for field in fields0.iter_mut() {
 //!         // We can get a location-independent alias for any type
 //!         // used in the original item definition.
 //!         let mut aliased_ty = telety0.alias_of(&field.ty).unwrap();
-//!         // Switch to `crate::...` if in the same crate the alias was defined,
-//!         // otherwise keep the path as `::my_crate::...`.
-//!         telety::visitor::Crateify::new().visit_type_mut(&mut aliased_ty);
+//!         crateify.visit_type_mut(&mut aliased_ty);
 //!         field.ty = aliased_ty;
 //!     }
 //!     for field in fields1.iter_mut() {
 //!         let mut aliased_ty = telety1.alias_of(&field.ty).unwrap();
-//!         telety::visitor::Crateify::new().visit_type_mut(&mut aliased_ty);
+//!         crateify.visit_type_mut(&mut aliased_ty);
 //!         field.ty = aliased_ty;
 //!     }
 //!
@@ -127,6 +128,88 @@
 //!     }
 //! }
 //! ```
+//! ### With a single function
+//! The [`#[reflect]`](reflect) attribute generates both proc macros from one function which receives a [Telety] for each path.
+//! See the [driver] module to use the stages directly.
+//! ```rust,ignore
+//! /// mix!(path_to_struct0, path_to_struct1; new_struct_ident)
+//! #[telety::reflect]
+//! fn mix(items: &[Telety], args: TokenStream) -> syn::Result<TokenStream> {
+//!     // ...
+//! }
+//! ```
+//! ### Without a proc macro
+//! For simpler cases, [with_ty!], [with_fields!] and [with_variants!] forward telety information
+//! directly to a `macro_rules!` macro:
+//! ```rust
+//! # use telety::telety;
+//! #[telety(crate)]
+//! pub struct Water {
+//!     pub water_liters: f32,
+//! }
+//!
+//! macro_rules! field_names {
+//!     ({ $($name:ident : $ty:ty),* }) => {
+//!         const FIELD_NAMES: &[&str] = &[$(stringify!($name)),*];
+//!     };
+//! }
+//!
+//! telety::with_fields!(Water => field_names! {});
+//! # fn main() { }
+//! ```
+//! ### Third-party items
+//! Items from other crates cannot have the attribute applied, but a proxy can provide their definition.
+//! The `proxy` argument re-exports the item along with a telety macro for the attributed definition,
+//! which is otherwise discarded. Fields which are not public can be omitted from the definition,
+//! if it is marked `#[non_exhaustive]`.
+//! ```rust
+//! # mod third_party { pub struct Map<K, V> { pub len: usize, entries: Vec<(K, V)> } }
+//! # use telety::telety;
+//! #[telety(crate, proxy = "third_party::Map")]
+//! #[non_exhaustive]
+//! pub struct Map<K, V> {
+//!     pub len: usize,
+//! }
+//! # fn main() { }
+//! ```
+//! The generic parameters, fields and variants of struct and enum proxies are checked against the proxied item,
+//! so a proxy which does not match the item does not compile:
+//! ```rust,compile_fail
+//! # mod third_party { pub struct Map<K, V> { pub len: usize, entries: Vec<(K, V)> } }
+//! # use telety::telety;
+//! #[telety(crate, proxy = "third_party::Map")]
+//! #[non_exhaustive]
+//! pub struct Map<K, V> {
+//!     pub len: u32,
+//! }
+//! # fn main() { }
+//! ```
+//! Only definitions marked `#[non_exhaustive]` may omit fields or variants:
+//! ```rust,compile_fail
+//! # mod third_party { pub enum Value { Null, One(i32) } }
+//! # use telety::telety;
+//! #[telety(crate, proxy = "third_party::Value")]
+//! pub enum Value {
+//!     Null,
+//! }
+//! # fn main() { }
+//! ```
+//! Enums, structs and variants which are `#[non_exhaustive]` in another crate must be marked in the definition,
+//! and their unlisted variants and fields are not checked:
+//! ```rust
+//! # use telety::telety;
+//! #[telety(crate, proxy = "std::io::ErrorKind")]
+//! #[non_exhaustive]
+//! pub enum ErrorKind {
+//!     NotFound,
+//!     PermissionDenied,
+//! }
+//! # fn main() { }
+//! ```
+//!
+//! With the `std-proxies` feature, `telety::std` contains proxies
+//! for common `std` types and traits (e.g. `Option`, `Vec`, `HashMap` and `Debug`), and
+//! `Apply::with_std_proxy_fallback` uses them when a `std` path does not have its own telety macro.
 //! # Limitations
 //! * telety is not yet robust in handling all features of types.
 //!   Expect failures if your types have lifetimes, const generics, associated types, impl types, or dyn types.
@@ -163,6 +246,9 @@
 //! [Apply::with_fallback] utilizes special language techniques to allow for fallback behavior,
 //! instead of resulting in a compile error (subject to some limitations, be sure to review the documentation).
 
+#[cfg(feature = "std-proxies")]
+pub mod std;
+
 pub mod util {
     //! Macros which may be useful to use with [telety](super::telety).
 
@@ -192,14 +278,109 @@ pub mod util {
     ///     println!("{}", MaybeMacro.to_string());
     /// }
     /// ```
+    /// Prefix the input with `expr =>` to use `try_invoke!` in expression position,
+    /// such as in a function body. The invocation and the fallback must both be expressions.
+    /// ```rust
+    /// # use telety_macro::try_invoke;
+    /// struct MaybeMacro;
+    /// fn main() {
+    ///     let value: i32 = try_invoke!(expr => self::MaybeMacro!(1); 2);
+    ///     assert_eq!(value, 2);
+    /// }
+    /// ```
+    /// Several candidate invocations can be separated by `else`. The first candidate
+    /// whose path is a macro is invoked, or the fallback if none are.
+    /// ```rust
+    /// # use telety_macro::try_invoke;
+    /// struct First;
+    /// macro_rules! second {
+    ///     ($value:literal) => { $value };
+    /// }
+    /// fn main() {
+    ///     let value: i32 = try_invoke!(expr => self::First!(1) else second!(2) else self::First!(3); 4);
+    ///     assert_eq!(value, 2);
+    /// }
+    /// ```
+    /// For type position, see [Select].
     #[doc(inline)]
     pub use telety_macro::try_invoke;
+
+    /// Expands to a `const` [bool] expression, which is `true` if the path has a telety-generated macro.
+    /// The path must exist in some namespace (e.g. as a type), like [try_invoke].
+    /// ```rust
+    /// # use telety::{telety, util::is_telety};
+    /// #[telety(crate)]
+    /// pub struct WithTelety;
+    ///
+    /// pub struct WithoutTelety;
+    ///
+    /// pub struct UnrelatedMacro;
+    /// mod unrelated {
+    ///     // A macro with the same name, which is not telety-generated
+    ///     macro_rules! UnrelatedMacro {
+    ///         ($($tokens:tt)*) => {};
+    ///     }
+    ///     pub(crate) use UnrelatedMacro;
+    /// }
+    /// pub(crate) use unrelated::UnrelatedMacro;
+    ///
+    /// const WITH: bool = is_telety!(crate::WithTelety);
+    /// const WITHOUT: bool = is_telety!(crate::WithoutTelety);
+    /// const UNRELATED: bool = is_telety!(crate::UnrelatedMacro);
+    /// fn main() {
+    ///     assert!(WITH);
+    ///     assert!(!WITHOUT);
+    ///     assert!(!UNRELATED);
+    /// }
+    /// ```
+    #[doc(inline)]
+    pub use telety_macro::is_telety;
+
+    /// Selects between two types with a `const` [bool], through [Choose].
+    /// Combined with [is_telety], this chooses a type in type position, where
+    /// [try_invoke] cannot be used.
+    /// ```rust
+    /// # use telety::{telety, util::{Choose, Select, is_telety}};
+    /// #[telety(crate)]
+    /// pub struct WithTelety;
+    ///
+    /// pub struct WithoutTelety;
+    ///
+    /// type A = <Select<{ is_telety!(crate::WithTelety) }> as Choose<u8, &'static str>>::Output;
+    /// type B = <Select<{ is_telety!(crate::WithoutTelety) }> as Choose<u8, &'static str>>::Output;
+    /// fn main() {
+    ///     let a: A = 1;
+    ///     let b: B = "b";
+    /// }
+    /// ```
+    pub struct Select<const CONDITION: bool>;
+
+    /// Implemented by [Select]. `Output` is `Then` if the condition is `true`, otherwise `Otherwise`.
+    pub trait Choose<Then: ?Sized, Otherwise: ?Sized> {
+        /// The selected type
+        type Output: ?Sized;
+    }
+
+    impl<Then: ?Sized, Otherwise: ?Sized> Choose<Then, Otherwise> for Select<true> {
+        type Output = Then;
+    }
+
+    impl<Then: ?Sized, Otherwise: ?Sized> Choose<Then, Otherwise> for Select<false> {
+        type Output = Otherwise;
+    }
 }
 
 #[doc(hidden)]
 pub mod __private {
+    pub use syn;
+    pub use telety_macro::apply_arguments;
     pub use telety_macro::crateify;
+    #[cfg(feature = "v1")]
+    pub use telety_macro::deep_step;
+    pub use telety_macro::downgrade;
     pub use telety_macro::find_and_replace;
+    pub use telety_macro::find_and_replace_multi;
+    pub use telety_macro::handshake;
 
     /// The technique that allows aliasing privately `use`d public types does not work for traits.
     /// You must refer to the trait with a crate/module prefix in order to use the alias
@@ -210,6 +391,87 @@ pub mod __private {
 #[doc(inline)]
 pub use telety_macro::telety;
 
+#[doc(inline)]
+#[cfg(feature = "v1")]
+pub use telety_macro::reflect;
+
+/// Invoke a `macro_rules!` macro with the definition of a telety item, without writing a proc macro.  
+/// `with_ty!(path::to::Item => callback! { extra })` invokes `callback! { [definition] extra }`.
+/// The definition includes the `#[telety]` attribute, and its types are not aliased.
+/// ```rust
+/// # use telety::telety;
+/// #[telety(crate)]
+/// pub struct Point {
+///     pub x: f32,
+/// }
+///
+/// macro_rules! item_name {
+///     ([$(#[$attr:meta])* $vis:vis struct $name:ident $($rest:tt)*] $const_ident:ident) => {
+///         const $const_ident: &str = stringify!($name);
+///     };
+/// }
+///
+/// telety::with_ty!(Point => item_name! { NAME });
+/// # fn main() {
+/// assert_eq!(NAME, "Point");
+/// # }
+/// ```
+#[doc(inline)]
+#[cfg(feature = "v1")]
+pub use telety_macro::with_ty;
+
+/// Invoke a `macro_rules!` macro with the fields of a telety struct or union, without writing a proc macro.  
+/// `with_fields!(path::to::Item => callback! { extra })` invokes `callback! { fields extra }`,
+/// where `fields` is in the format of [v2::FIELDS].
+/// ```rust
+/// # use telety::telety;
+/// #[telety(crate)]
+/// pub struct Point {
+///     pub x: f32,
+///     pub y: f32,
+/// }
+///
+/// macro_rules! field_names {
+///     ({ $($name:ident : $ty:ty),* } $const_ident:ident) => {
+///         const $const_ident: &[&str] = &[$(stringify!($name)),*];
+///     };
+/// }
+///
+/// telety::with_fields!(Point => field_names! { FIELD_NAMES });
+/// # fn main() {
+/// assert_eq!(FIELD_NAMES, ["x", "y"]);
+/// # }
+/// ```
+#[doc(inline)]
+#[cfg(feature = "v2")]
+pub use telety_macro::with_fields;
+
+/// Invoke a `macro_rules!` macro with the variants of a telety enum, without writing a proc macro.  
+/// `with_variants!(path::to::Item => callback! { extra })` invokes `callback! { variants extra }`,
+/// where `variants` is in the format of [v2::VARIANTS].
+/// ```rust
+/// # use telety::telety;
+/// #[telety(crate)]
+/// pub enum Shape {
+///     Circle(f32),
+///     Square { side: f32 },
+/// }
+///
+/// macro_rules! variant_count {
+///     ({ $($variant:ident $fields:tt $(= $discriminant:expr)?),* } $const_ident:ident) => {
+///         const $const_ident: usize = [$(stringify!($variant)),*].len();
+///     };
+/// }
+///
+/// telety::with_variants!(Shape => variant_count! { VARIANT_COUNT });
+/// # fn main() {
+/// assert_eq!(VARIANT_COUNT, 2);
+/// # }
+/// ```
+#[doc(inline)]
+#[cfg(feature = "v2")]
+pub use telety_macro::with_variants;
+
 pub mod visitor {
     //! `syn` visitors for use with `telety` information.
 
@@ -251,6 +513,9 @@ pub mod visitor {
 pub mod alias {
     #[doc(inline)]
     pub use telety_impl::alias::{Map, Module};
+
+    #[doc(inline)]
+    pub use telety_impl::interop::AliasMapArgs;
 }
 
 #[doc(inline)]
@@ -259,10 +524,23 @@ pub use telety_impl::Alias;
 #[doc(inline)]
 pub use telety_impl::Apply;
 #[doc(inline)]
+pub use telety_impl::Batch;
+#[doc(inline)]
 pub use telety_impl::Command;
 #[doc(inline)]
 pub use telety_impl::Telety;
 
+#[doc(inline)]
+#[cfg(feature = "v1")]
+pub use telety_impl::deep;
+
+#[doc(inline)]
+#[cfg(feature = "v1")]
+pub use telety_impl::driver;
+
+#[doc(inline)]
+pub use telety_impl::extension;
+
 #[doc(inline)]
 /// Always available utility [Command]s
 pub use telety_impl::version::v0;
@@ -271,3 +549,8 @@ pub use telety_impl::version::v0;
 #[cfg(feature = "v1")]
 /// Version 1 [Command] API
 pub use telety_impl::version::v1;
+
+#[doc(inline)]
+#[cfg(feature = "v2")]
+/// Version 2 [Command] API. Adds [Command]s which accept arguments (see [Apply::with_arguments]).
+pub use telety_impl::version::v2;
@@ -0,0 +1,185 @@
+//! Proxy telety items for common `std` types and traits.
+//! Each proxy re-exports the original item, along with a telety-generated macro describing its definition.
+//! Fields which are private in `std` are omitted, so the proxies of opaque types (e.g. [Vec]) have no fields,
+//! and are marked `#[non_exhaustive]`.
+//! ```rust
+//! # use telety::util::is_telety;
+//! const { assert!(is_telety!(telety::std::option::Option)) };
+//! ```
+//! [Apply::with_std_proxy_fallback](crate::Apply::with_std_proxy_fallback) uses these proxies
+//! if a `std` path (e.g. `::core::option::Option`) does not have its own telety macro.
+
+/// Proxies for [core::option]
+pub mod option {
+    use crate::telety;
+
+    #[telety(crate::std::option, proxy = "::core::option::Option")]
+    pub enum Option<T> {
+        None,
+        Some(T),
+    }
+}
+
+/// Proxies for [core::result]
+pub mod result {
+    use crate::telety;
+
+    #[telety(crate::std::result, proxy = "::core::result::Result")]
+    pub enum Result<T, E> {
+        Ok(T),
+        Err(E),
+    }
+}
+
+/// Proxies for [::std::vec](mod@::std::vec)
+pub mod vec {
+    use crate::telety;
+
+    #[telety(crate::std::vec, proxy = "::std::vec::Vec")]
+    #[non_exhaustive]
+    pub struct Vec<T> {}
+}
+
+/// Proxies for [::std::boxed]
+pub mod boxed {
+    use crate::telety;
+
+    #[telety(crate::std::boxed, proxy = "::std::boxed::Box")]
+    #[non_exhaustive]
+    pub struct Box<T: ?Sized> {}
+}
+
+/// Proxies for [::std::string]
+pub mod string {
+    use crate::telety;
+
+    #[telety(crate::std::string, proxy = "::std::string::String")]
+    #[non_exhaustive]
+    pub struct String {}
+}
+
+/// Proxies for [::std::borrow]
+pub mod borrow {
+    use crate::telety;
+
+    #[telety(crate::std::borrow, proxy = "::std::borrow::Cow")]
+    pub enum Cow<'a, B: ?Sized + ::std::borrow::ToOwned + 'a> {
+        Borrowed(&'a B),
+        Owned(<B as ::std::borrow::ToOwned>::Owned),
+    }
+}
+
+/// Proxies for [::std::collections]
+pub mod collections {
+    use crate::telety;
+
+    #[telety(crate::std::collections, proxy = "::std::collections::HashMap")]
+    #[non_exhaustive]
+    pub struct HashMap<K, V, S = ::std::hash::RandomState> {}
+
+    #[telety(crate::std::collections, proxy = "::std::collections::HashSet")]
+    #[non_exhaustive]
+    pub struct HashSet<T, S = ::std::hash::RandomState> {}
+
+    #[telety(crate::std::collections, proxy = "::std::collections::BTreeMap")]
+    #[non_exhaustive]
+    pub struct BTreeMap<K, V> {}
+
+    #[telety(crate::std::collections, proxy = "::std::collections::BTreeSet")]
+    #[non_exhaustive]
+    pub struct BTreeSet<T> {}
+}
+
+/// Proxies for [::std::rc]
+pub mod rc {
+    use crate::telety;
+
+    #[telety(crate::std::rc, proxy = "::std::rc::Rc")]
+    #[non_exhaustive]
+    pub struct Rc<T: ?Sized> {}
+}
+
+/// Proxies for [::std::sync]
+pub mod sync {
+    use crate::telety;
+
+    #[telety(crate::std::sync, proxy = "::std::sync::Arc")]
+    #[non_exhaustive]
+    pub struct Arc<T: ?Sized> {}
+}
+
+/// Proxies for [core::clone]
+pub mod clone {
+    use crate::telety;
+
+    #[telety(crate::std::clone, proxy = "::core::clone::Clone")]
+    pub trait Clone: ::core::marker::Sized {
+        fn clone(&self) -> Self;
+    }
+}
+
+/// Proxies for [core::marker]
+pub mod marker {
+    use crate::telety;
+
+    #[telety(crate::std::marker, proxy = "::core::marker::Copy")]
+    pub trait Copy: ::core::clone::Clone {}
+}
+
+/// Proxies for [core::default]
+pub mod default {
+    use crate::telety;
+
+    #[telety(crate::std::default, proxy = "::core::default::Default")]
+    pub trait Default: ::core::marker::Sized {
+        fn default() -> Self;
+    }
+}
+
+/// Proxies for [core::fmt]
+pub mod fmt {
+    use crate::telety;
+
+    #[telety(crate::std::fmt, proxy = "::core::fmt::Debug")]
+    pub trait Debug {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result;
+    }
+
+    #[telety(crate::std::fmt, proxy = "::core::fmt::Display")]
+    pub trait Display {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result;
+    }
+}
+
+/// Proxies for [core::cmp]
+pub mod cmp {
+    use crate::telety;
+
+    #[telety(crate::std::cmp, proxy = "::core::cmp::PartialEq")]
+    pub trait PartialEq<Rhs: ?Sized = Self> {
+        fn eq(&self, other: &Rhs) -> ::core::primitive::bool;
+    }
+
+    #[telety(crate::std::cmp, proxy = "::core::cmp::Eq")]
+    pub trait Eq: ::core::cmp::PartialEq {}
+
+    #[telety(crate::std::cmp, proxy = "::core::cmp::PartialOrd")]
+    pub trait PartialOrd<Rhs: ?Sized = Self>: ::core::cmp::PartialEq<Rhs> {
+        fn partial_cmp(&self, other: &Rhs) -> ::core::option::Option<::core::cmp::Ordering>;
+    }
+
+    #[telety(crate::std::cmp, proxy = "::core::cmp::Ord")]
+    pub trait Ord: ::core::cmp::Eq + ::core::cmp::PartialOrd {
+        fn cmp(&self, other: &Self) -> ::core::cmp::Ordering;
+    }
+}
+
+/// Proxies for [core::hash]
+pub mod hash {
+    use crate::telety;
+
+    #[telety(crate::std::hash, proxy = "::core::hash::Hash")]
+    pub trait Hash {
+        fn hash<H: ::core::hash::Hasher>(&self, state: &mut H);
+    }
+}
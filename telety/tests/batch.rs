@@ -0,0 +1,34 @@
+mod util;
+
+pub struct NotTelety;
+
+// The constants are only visible in the module if the output of entries with fallbacks is forwarded
+// out of the blocks `try_invoke!` expands in
+mod batched {
+    telety_test_macros::batch!(MANUAL, forward = __batch_manual;
+        crate::util::types::Simple,
+        crate::NotTelety else { fallback },
+    );
+
+    telety_test_macros::batch!(AUTO, forward = auto;
+        crate::NotTelety else { first },
+        crate::util::types::MyEnum,
+        crate::NotTelety else { second },
+    );
+}
+
+#[test]
+fn add_with_fallback() {
+    assert_eq!(
+        batched::AUTO,
+        ["first", ":: batch :: util :: types :: MyEnum", "second"]
+    );
+}
+
+#[test]
+fn with_macro_forwarding() {
+    assert_eq!(
+        batched::MANUAL,
+        [":: batch :: util :: types :: Simple", "fallback"]
+    );
+}
@@ -1,5 +1,10 @@
 mod util;
 
+/// Items generated by a `macro_rules!` macro from another crate
+mod generated {
+    producer::generate!(Consumed in crate::generated);
+}
+
 mod v0 {
     use super::*;
 
@@ -10,6 +15,70 @@ mod v0 {
             assert_eq!(stringify!(__PARAM__), ":: commands :: util :: types :: MyEnum");
         );
     }
+
+    #[test]
+    fn ignored_arguments() {
+        // Commands from before arguments were supported accept and ignore them
+        self::util::types::MyEnum!(
+            0, path(unused arguments), __PARAM__,
+            assert_eq!(stringify!(__PARAM__), ":: commands :: util :: types :: MyEnum");
+        );
+    }
+
+    #[test]
+    fn versions() {
+        self::util::types::Simple!(
+            0, versions, __PARAM__,
+            let versions: &[usize] = &__PARAM__;
+        );
+        assert_eq!(versions.first(), Some(&0));
+        assert_eq!(versions.contains(&1), cfg!(feature = "v1"));
+        assert_eq!(versions.contains(&2), cfg!(feature = "v2"));
+    }
+
+    #[test]
+    fn downgrade() {
+        // Version 9 is not supported, so the command falls back to version 0
+        self::util::types::Simple!(
+            0, versions, __VERSIONS__,
+            telety::__private::downgrade!(
+                __VERSIONS__, [9, 0], path, self::util::types::Simple, __PARAM__, [],
+                assert_eq!(stringify!(__PARAM__), ":: commands :: util :: types :: Simple");
+            );
+        );
+    }
+
+    #[test]
+    fn downgrade_fallback() {
+        self::util::types::Simple!(
+            0, versions, __VERSIONS__,
+            telety::__private::downgrade!(
+                __VERSIONS__, [9], path, self::util::types::Simple, __PARAM__, [{
+                    let path = "fallback";
+                }],
+                let path = stringify!(__PARAM__);
+            );
+        );
+        assert_eq!(path, "fallback");
+    }
+
+    #[test]
+    fn dollar_crate_path() {
+        self::util::types::Generated!(
+            0, path, __PARAM__,
+            assert_eq!(stringify!(__PARAM__), "$crate :: util :: types :: Generated");
+        );
+    }
+
+    #[test]
+    fn dollar_crate_path_foreign() {
+        // `$crate` refers to the crate defining the `macro_rules!` macro, not this crate
+        producer::generated::Generated!(
+            0, path, __PARAM__,
+            let generated: __PARAM__ = producer::generated::Generated(producer::shapes::Point { x: 1, y: 2 }, None);
+        );
+        assert_eq!((generated.0.x, generated.0.y), (1, 2));
+    }
 }
 
 #[cfg(feature = "v1")]
@@ -24,6 +93,59 @@ mod v1 {
         );
     }
 
+    #[test]
+    fn ignored_arguments() {
+        self::util::types::MyEnum!(
+            1, unique_ident(unused arguments), __PARAM__,
+            assert_eq!(stringify!(__PARAM__), "commands_util_types_MyEnum");
+        );
+    }
+
+    #[test]
+    fn dollar_crate_unique_ident() {
+        self::util::types::Generated!(
+            1, unique_ident, __PARAM__,
+            assert_eq!(stringify!(__PARAM__), "__dollar_crate_util_types_Generated");
+        );
+    }
+
+    #[test]
+    fn multi() {
+        self::util::types::Simple!(
+            0, multi, [(0, path, __PATH__), (1, unique_ident, __IDENT__)],
+            assert_eq!(
+                stringify!(__PATH__ __IDENT__),
+                ":: commands :: util :: types :: Simple commands_util_types_Simple"
+            );
+        );
+    }
+
+    #[allow(dead_code)]
+    pub struct NotTelety;
+
+    mod multi_applied {
+        telety_test_macros::multi_const!(WITH, crate::util::types::Simple);
+        telety_test_macros::multi_const!(WITHOUT, crate::v1::NotTelety);
+    }
+
+    #[test]
+    fn apply_multi() {
+        const EXPECTED: &str = ":: commands :: util :: types :: Simple commands_util_types_Simple";
+
+        assert_eq!(
+            telety_test_macros::multi_or!(crate::util::types::Simple, "none"),
+            EXPECTED
+        );
+        assert_eq!(
+            telety_test_macros::multi_or!(crate::v1::NotTelety, "none"),
+            "none"
+        );
+
+        // In item position, forwarded through a macro
+        assert_eq!(multi_applied::WITH, EXPECTED);
+        assert_eq!(multi_applied::WITHOUT, "none");
+    }
+
     #[test]
     fn ty() {
         self::util::types::Simple!(
@@ -34,4 +156,523 @@ mod v1 {
             );
         );
     }
+
+    #[test]
+    fn ty_dollar_crate() {
+        // `crate` paths in the definition still refer to the item's crate outside of the telety-generated macro
+        producer::Labeled!(
+            1, ty, __PARAM__,
+            let definition = stringify!(__PARAM__);
+        );
+        assert!(definition.contains("pub point : $crate :: shapes :: Point"));
+    }
+}
+
+#[cfg(feature = "v2")]
+mod v2 {
+    use super::*;
+
+    #[test]
+    fn ty_generics() {
+        #[allow(dead_code)]
+        mod monomorphized {
+            super::util::types::MyGeneric!(
+                2,
+                ty(generics = [crate::util::types::Simple]),
+                __PARAM__,
+                __PARAM__
+            );
+        }
+
+        use self::util::types::{MyEmpty, MyGeneric, Simple};
+
+        // `T` is substituted in each variant, including in arrays
+        let monomorphized::MyGeneric::B([first, second]) =
+            monomorphized::MyGeneric::B([Simple(1), Simple(2)])
+        else {
+            unreachable!()
+        };
+        assert_eq!((first.0, second.0), (1, 2));
+
+        // Aliased types, including `Self`, refer to the original items with the substituted arguments
+        let nested = monomorphized::MyGeneric::C(Box::new(MyGeneric::<Simple>::A(Simple(3))));
+        let monomorphized::MyGeneric::C(inner) = nested else {
+            unreachable!()
+        };
+        assert!(matches!(*inner, MyGeneric::A(Simple(3))));
+        let _ = monomorphized::MyGeneric::D(MyEmpty);
+    }
+
+    #[test]
+    fn fingerprint() {
+        self::util::types::MyGeneric!(
+            2, ty, __DEFINITION__,
+            let definition = stringify!(__DEFINITION__);
+        );
+
+        let item: syn::Item = syn::parse_str(definition).unwrap();
+        assert!(telety::Telety::new(&item).is_ok());
+
+        // A consumer which identifies different aliases than the producer
+        let start = definition.find("fingerprint = \"").unwrap() + "fingerprint = \"".len();
+        let mut tampered = definition.to_string();
+        tampered.replace_range(start..start + 16, "0123456789abcdef");
+        let item: syn::Item = syn::parse_str(&tampered).unwrap();
+        let Err(error) = telety::Telety::new(&item) else {
+            panic!("Expected a fingerprint mismatch");
+        };
+        assert!(error.to_string().contains("fingerprint mismatch"));
+    }
+
+    #[test]
+    fn foreign_definition() {
+        // Arguments are applied in this crate, with a [Telety] recreated from the other crate's definition
+        producer::Labeled!(
+            2, ty(generics = [u8]), __ITEM__,
+            let item = stringify!(__ITEM__);
+        );
+        assert!(item.contains("pub value : u8"));
+    }
+
+    #[test]
+    fn apply_downgrade() {
+        // The item supports version 2, so it is used
+        telety_test_macros::downgrade!(
+            2 ty, self::util::types::Simple, __PARAM__,
+            let definition = stringify!(__PARAM__);
+        );
+        assert!(definition.contains("fingerprint"));
+
+        // An item from a release without the `versions` command falls back to version 1
+        telety_test_macros::downgrade!(
+            2 ty, self::util::types::Released, __PARAM__,
+            let definition = stringify!(__PARAM__);
+        );
+        assert_eq!(
+            definition,
+            "#[telety(:: commands :: util :: types)] pub struct Released(pub i32);"
+        );
+    }
+
+    #[test]
+    fn renamed_dependency() {
+        // `telety-test-producer` is renamed to `producer`, so the aliases in the definition
+        // only resolve if they use the renamed path
+        #[allow(dead_code)]
+        mod monomorphized {
+            producer::Labeled!(2, ty(generics = [u8]), __PARAM__, __PARAM__);
+        }
+
+        let labeled = monomorphized::Labeled {
+            label: "origin".to_string(),
+            point: producer::shapes::Point { x: 0, y: 0 },
+            value: 1u8,
+        };
+        assert_eq!(labeled.value, 1);
+    }
+
+    #[test]
+    fn field() {
+        self::util::types::Attributed!(
+            2, field(field), __PARAM__,
+            let field: __PARAM__ = Some(1i32);
+        );
+        assert_eq!(field, Some(1));
+
+        // Aliases of `$crate` paths from an item generated by `macro_rules!`
+        self::util::types::Generated!(
+            2, field(1), __PARAM__,
+            let field: __PARAM__ = Some(self::util::types::Unit);
+        );
+        assert!(field.is_some());
+
+        // Aliases of `$crate` paths, from a `macro_rules!` macro of another crate
+        producer::generated::Generated!(
+            2, field(1), __PARAM__,
+            let field: __PARAM__ = Some(producer::shapes::Point { x: 1, y: 2 });
+        );
+        assert!(field.is_some());
+        self::generated::Consumed!(
+            2, field(0), __PARAM__,
+            let point: __PARAM__ = producer::shapes::Point { x: 3, y: 4 };
+        );
+        assert_eq!((point.x, point.y), (3, 4));
+    }
+
+    #[test]
+    fn fields() {
+        macro_rules! field_names {
+            ({ $($name:ident : $ty:ty),* }) => {
+                [$(stringify!($name)),*]
+            };
+        }
+
+        macro_rules! default_tuple {
+            (( $($index:tt : $ty:ty),* )) => {
+                ($(<$ty>::default(),)*)
+            };
+        }
+
+        self::util::types::Attributed!(
+            2, fields, __PARAM__,
+            let names = field_names!(__PARAM__);
+        );
+        assert_eq!(names, ["field", "other"]);
+
+        self::util::types::Simple!(
+            2, fields, __PARAM__,
+            let tuple = default_tuple!(__PARAM__);
+        );
+        assert_eq!(tuple, (0i32,));
+
+        self::util::types::Unit!(
+            2, fields, __PARAM__,
+            assert_eq!(stringify!(__PARAM__), ";");
+        );
+    }
+
+    #[test]
+    fn helper_attrs() {
+        macro_rules! helpers {
+            ({ $($(#[$attr:meta])* $name:ident : $ty:ty),* }) => {
+                [$((stringify!($name), <[&str]>::len(&[$(stringify!($attr)),*]))),*]
+            };
+        }
+
+        self::util::types::Helpers!(
+            2, fields, __PARAM__,
+            let fields = helpers!(__PARAM__);
+        );
+        assert_eq!(fields, [("field", 2), ("other", 0)]);
+
+        macro_rules! variant_helpers {
+            ({ $($(#[$attr:meta])* $variant:ident $fields:tt),* }) => {
+                [$((stringify!($variant), stringify!($($attr)*), stringify!($fields))),*]
+            };
+        }
+
+        self::util::types::HelperVariants!(
+            2, variants, __PARAM__,
+            let variants = variant_helpers!(__PARAM__);
+        );
+        assert_eq!(variants[0], ("A", "mix(skip)", ";"));
+        assert_eq!(variants[1].1, "");
+        assert!(variants[1].2.starts_with("(#[mix(rename = \"b\")] 0 :"));
+
+        // The helper attributes are kept in the definition
+        self::util::types::Helpers!(
+            1, ty, __DEFINITION__,
+            let definition = stringify!(__DEFINITION__);
+        );
+        let item: syn::Item = syn::parse_str(definition).unwrap();
+        let telety = telety::Telety::new(&item).unwrap();
+        let helpers: Vec<_> = telety
+            .helper_attributes()
+            .map(|attr| attr.meta.require_list().unwrap().tokens.to_string())
+            .collect();
+        assert_eq!(helpers, ["prefix = \"helped\""]);
+    }
+
+    #[test]
+    fn variants() {
+        macro_rules! discriminant {
+            () => {
+                None
+            };
+            ($discriminant:expr) => {
+                Some($discriminant)
+            };
+        }
+
+        macro_rules! variants {
+            ({ $($variant:ident $fields:tt $(= $discriminant:expr)?),* }) => {
+                [$((stringify!($variant), stringify!($fields), discriminant!($($discriminant)?))),*]
+            };
+        }
+
+        self::util::types::Tagged!(
+            2, variants, __PARAM__,
+            let variants = variants!(__PARAM__);
+        );
+        assert_eq!(variants.len(), 4);
+        assert_eq!(variants[0], ("A", ";", Some(1u8)));
+        assert_eq!(variants[1].2, Some(2));
+        assert_eq!(variants[2].0, "C");
+        assert_eq!(variants[3], ("D", ";", None));
+    }
+
+    #[test]
+    fn alias_map() {
+        fn check(args: &str, definition: &str) {
+            let args: telety::alias::AliasMapArgs = syn::parse_str(args).unwrap();
+            let item: syn::Item = syn::parse_str(definition).unwrap();
+
+            let map = telety::alias::Map::from_args(args).unwrap();
+            let shipped = telety::Telety::with_alias_map(&item, map).unwrap();
+            let derived = telety::Telety::new(&item).unwrap();
+
+            let aliases = |telety: &telety::Telety| {
+                let mut aliases: Vec<_> = telety
+                    .alias_map()
+                    .iter_aliases()
+                    .map(|alias| {
+                        let aliased_path = alias.aliased_path();
+                        let path = alias.to_path();
+                        quote::quote!(#aliased_path => #path).to_string()
+                    })
+                    .collect();
+                aliases.sort();
+                aliases
+            };
+
+            assert_eq!(aliases(&shipped), aliases(&derived));
+            assert_eq!(
+                quote::ToTokens::into_token_stream(shipped.alias_map().to_args()).to_string(),
+                quote::ToTokens::into_token_stream(derived.alias_map().to_args()).to_string()
+            );
+        }
+
+        self::util::types::MyGeneric!(
+            0, multi, [(2, alias_map, __ARGS__), (1, ty, __DEFINITION__)],
+            check(stringify!(__ARGS__), stringify!(__DEFINITION__));
+        );
+        // Includes a trait alias
+        self::util::types::AlwaysAlias!(
+            0, multi, [(2, alias_map, __ARGS__), (1, ty, __DEFINITION__)],
+            check(stringify!(__ARGS__), stringify!(__DEFINITION__));
+        );
+    }
+
+    #[test]
+    fn alias_modes() {
+        fn aliased_paths(args: &str) -> Vec<String> {
+            let args: telety::alias::AliasMapArgs = syn::parse_str(args).unwrap();
+            let map = telety::alias::Map::from_args(args).unwrap();
+            let mut paths: Vec<_> = map
+                .iter_aliases()
+                .map(|alias| quote::ToTokens::to_token_stream(alias.aliased_path()).to_string())
+                // The item itself is always aliased
+                .filter(|path| !path.starts_with(":: commands"))
+                .collect();
+            paths.sort();
+            paths
+        }
+
+        self::util::types::Modes!(
+            2, alias_map, __ARGS__,
+            let paths = aliased_paths(stringify!(__ARGS__));
+        );
+        assert_eq!(paths, ["B", "Unit"]);
+
+        self::util::types::ModeTrait!(
+            2, alias_map, __ARGS__,
+            let paths = aliased_paths(stringify!(__ARGS__));
+        );
+        assert_eq!(paths, ["PubTrait", "Simple"]);
+
+        let item: syn::Item = syn::parse_quote! {
+            #[telety(crate::my_mod)]
+            pub struct Invalid {
+                #[telety(alias)]
+                associated: <u8 as Trait>::Assoc,
+                #[telety(unknown)]
+                unknown: u8,
+            }
+        };
+        let error = telety::Telety::new(&item).err().unwrap();
+        assert_eq!(error.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn export_public_generics() {
+        // Private fields are left out of the substituted definition too
+        #[allow(dead_code)]
+        mod monomorphized {
+            super::util::types::ExportedGeneric!(2, ty(generics = [u8]), __PARAM__, __PARAM__);
+        }
+
+        let exported = monomorphized::ExportedGeneric {
+            value: 1u8,
+            simple: self::util::types::Simple(2),
+        };
+        assert_eq!((exported.value, exported.simple.0), (1, 2));
+
+        self::util::types::ExportedGeneric!(
+            2, ty(generics = [u8]), __DEFINITION__,
+            let definition = stringify!(__DEFINITION__);
+        );
+        assert!(definition.contains("pub value : u8"));
+        assert!(!definition.contains("hidden"));
+        assert!(!definition.contains("Secret"));
+    }
+
+    #[test]
+    fn export_public() {
+        macro_rules! fields {
+            ({ $($name:ident : $ty:ty),* }) => {
+                [$(stringify!($name)),*]
+            };
+            (( $($index:tt : $ty:ty),* )) => {
+                [$(stringify!($ty)),*]
+            };
+        }
+
+        self::util::types::Exported!(
+            2, fields, __PARAM__,
+            let names = fields!(__PARAM__);
+        );
+        assert_eq!(names, ["visible", "internal"]);
+
+        self::util::types::ExportedTuple!(
+            2, fields, __PARAM__,
+            let types = fields!(__PARAM__);
+        );
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0], "()");
+
+        // Removed fields are marked, and the private type is not aliased
+        self::util::types::Exported!(
+            1, ty, __DEFINITION__,
+            let definition = stringify!(__DEFINITION__);
+        );
+        let item: syn::Item = syn::parse_str(definition).unwrap();
+        let telety = telety::Telety::new(&item).unwrap();
+        assert!(
+            telety
+                .attributes()
+                .iter()
+                .any(|attr| attr.path().is_ident("non_exhaustive"))
+        );
+        assert!(
+            telety
+                .alias_map()
+                .iter_aliases()
+                .all(|alias| !alias.aliased_path().is_ident("Secret"))
+        );
+
+        // Default method bodies are removed
+        self::util::types::ExportedTrait!(
+            1, ty, __DEFINITION__,
+            let definition = stringify!(__DEFINITION__);
+        );
+        let syn::Item::Trait(item_trait) = syn::parse_str(definition).unwrap() else {
+            unreachable!()
+        };
+        let syn::TraitItem::Fn(provided) = &item_trait.items[0] else {
+            unreachable!()
+        };
+        assert!(provided.default.is_none());
+    }
+
+    #[test]
+    fn cfg() {
+        fn field_names(definition: &str) -> Vec<(String, usize)> {
+            let item: syn::Item = syn::parse_str(definition).unwrap();
+            let telety = telety::Telety::new(&item).unwrap();
+            let syn::Item::Struct(item_struct) = telety.item() else {
+                unreachable!()
+            };
+            item_struct
+                .fields
+                .iter()
+                .map(|field| (field.ident.as_ref().unwrap().to_string(), field.attrs.len()))
+                .collect()
+        }
+
+        self::util::types::Conditional!(
+            2, ty(cfg = preserved), __DEFINITION__,
+            let preserved = stringify!(__DEFINITION__);
+        );
+        assert_eq!(
+            field_names(preserved),
+            [
+                ("always".to_string(), 0),
+                ("disabled".to_string(), 1),
+                ("enabled".to_string(), 1),
+                ("attributed".to_string(), 1)
+            ]
+        );
+
+        self::util::types::Conditional!(
+            2, ty(cfg = resolved), __DEFINITION__,
+            let resolved = stringify!(__DEFINITION__);
+        );
+        // The cfgs of enabled fields are removed
+        assert_eq!(
+            field_names(resolved),
+            [
+                ("always".to_string(), 0),
+                ("enabled".to_string(), 0),
+                ("attributed".to_string(), 0)
+            ]
+        );
+
+        // The alias of a type which may not exist is only generated under the same cfg
+        let item: syn::Item = syn::parse_str(resolved).unwrap();
+        let telety = telety::Telety::new(&item).unwrap();
+        assert_eq!(telety.cfg_predicates().len(), 3);
+        let missing = telety
+            .alias_map()
+            .iter_aliases()
+            .find(|alias| alias.aliased_path().is_ident("Missing"))
+            .unwrap();
+        assert_eq!(
+            quote::ToTokens::to_token_stream(&missing.cfg().unwrap()).to_string(),
+            "any ()"
+        );
+
+        self::util::types::ConditionalVariants!(
+            2, ty(cfg = resolved), __DEFINITION__,
+            let resolved = stringify!(__DEFINITION__);
+        );
+        let item: syn::Item = syn::parse_str(resolved).unwrap();
+        let telety = telety::Telety::new(&item).unwrap();
+        let syn::Item::Enum(item_enum) = telety.item() else {
+            unreachable!()
+        };
+        assert_eq!(item_enum.variants.len(), 1);
+    }
+
+    #[test]
+    fn attrs() {
+        self::util::types::Attributed!(
+            2, attrs(doc, allow), __PARAM__,
+            assert_eq!(
+                stringify!(__PARAM__),
+                "#[doc = \" Documented\"] #[allow(clippy :: all)]"
+            );
+        );
+    }
+}
+
+mod extension {
+    use super::*;
+
+    #[test]
+    fn definition() {
+        self::util::types::Extended!(
+            ext, definition_ext, definition, __definition__,
+            assert!(__definition__.ends_with("pub struct Extended(pub i32);"));
+        );
+    }
+
+    #[test]
+    fn no_version_arms() {
+        // Extension commands are forwarded by the `extensions` arms, and have no arms of their own
+        const DEFINITION: telety::Command =
+            telety::Command::extension("definition_ext", "definition");
+
+        let item: syn::Item = syn::parse_quote!(
+            #[telety(crate::util::types)]
+            pub struct Extended(pub i32);
+        );
+        let telety = telety::Telety::new(&item).unwrap();
+        assert!(DEFINITION.generate_macro_arm(&telety).unwrap().is_none());
+        assert!(
+            DEFINITION
+                .generate_error_macro_arms(&telety)
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
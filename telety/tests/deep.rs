@@ -0,0 +1,36 @@
+#![cfg(feature = "v1")]
+
+mod util;
+
+/// Implemented by `telety_test_macros::deep!`
+trait Tree {
+    const TREE: &str;
+}
+
+struct ParentTree;
+struct ATree;
+struct LabeledTree;
+
+telety_test_macros::deep!(self::util::types::Parent, 3; ParentTree);
+telety_test_macros::deep!(self::util::types::A, 1; ATree);
+// Definitions from another crate
+telety_test_macros::deep!(producer::Labeled, 3; LabeledTree);
+
+#[test]
+fn cycle() {
+    // `Child` contains `Option<Box<Parent>>`
+    assert_eq!(
+        ParentTree::TREE,
+        "Parent { Child { _, _, Parent cycle }, _ }"
+    );
+}
+
+#[test]
+fn max_depth() {
+    assert_eq!(ATree::TREE, "A { B .., _ }");
+}
+
+#[test]
+fn foreign() {
+    assert_eq!(LabeledTree::TREE, "Labeled { _, Point { _ } }");
+}
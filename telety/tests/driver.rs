@@ -0,0 +1,67 @@
+#![cfg(feature = "v1")]
+
+use quote::quote;
+use syn::parse_quote;
+use telety::driver::{self, FirstStage};
+
+#[test]
+fn first_stage() {
+    let output = FirstStage::new(parse_quote!(::my_crate::my_macro_impl))
+        .expand(quote!(crate::A, crate::B; extra))
+        .unwrap()
+        .to_string();
+
+    assert!(output.starts_with("crate :: A ! { 1 , ty"));
+    assert!(output.contains(":: my_crate :: my_macro_impl !"));
+    assert!(output.contains("extra"));
+}
+
+#[test]
+fn second_stage() {
+    let input = quote! {
+        [
+            (crate::A) [#[telety(crate)] pub struct A(pub i32);],
+            (crate::B) [#[telety(crate)] pub struct B;]
+        ]
+        extra
+    };
+
+    let output = driver::second_stage(input, |items, args| {
+        let idents = items.iter().map(|item| item.macro_ident());
+        Ok(quote!(#(#idents)* #args))
+    })
+    .unwrap();
+
+    assert_eq!(output.to_string(), "A B extra");
+}
+
+#[test]
+fn second_stage_error() {
+    let input = quote! {
+        [(crate::A) [pub struct A;]]
+    };
+
+    let Err(error) = driver::second_stage(input, |_items, _args| Ok(quote!())) else {
+        panic!("Expected an error");
+    };
+
+    assert!(
+        error
+            .to_string()
+            .starts_with("Failed to read telety item 'crate :: A'")
+    );
+}
+
+// `describe!` is written with `#[telety::reflect]`
+telety_test_macros::describe!(producer::Labeled, producer::shapes::Point; DESCRIPTIONS);
+
+#[test]
+fn reflect() {
+    assert_eq!(
+        DESCRIPTIONS,
+        [
+            ":: telety_test_producer :: Labeled { label point value }",
+            ":: telety_test_producer :: shapes :: Point { x y }",
+        ]
+    );
+}
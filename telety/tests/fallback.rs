@@ -36,3 +36,254 @@ fn try_invoke() {
     assert_eq!(self::YesMacro::text(), "Yes");
     assert_eq!(self::NoMacro::text(), "No");
 }
+
+struct _Named;
+macro_rules! _Named {
+    ($ty:ident $text:literal) => {
+        impl $ty {
+            pub const fn text() -> &'static str {
+                $text
+            }
+        }
+    };
+}
+use _Named as Named;
+
+struct Chained;
+struct Unchained;
+
+#[test]
+fn try_invoke_chain() {
+    use telety_macro::try_invoke;
+
+    try_invoke!(self::NoMacro!(Chained "First")
+        else self::Named!(Chained "Second")
+        else self::Named!(Chained "Third");
+        impl Chained {
+            pub const fn text() -> &'static str {
+                "None"
+            }
+        }
+    );
+    try_invoke!(self::NoMacro!(Unchained "First")
+        else self::NoMacro!(Unchained "Second");
+        impl Unchained {
+            pub const fn text() -> &'static str {
+                "None"
+            }
+        }
+    );
+
+    assert_eq!(self::Chained::text(), "Second");
+    assert_eq!(self::Unchained::text(), "None");
+
+    assert_eq!(
+        try_invoke!(expr => self::NoMacro!(1) else self::Value!(2) else self::Value!(3); 0),
+        2
+    );
+    assert_eq!(
+        try_invoke!(expr => self::NoMacro!(1) else self::NoMacro!(2); 0),
+        0
+    );
+}
+
+#[telety::telety(crate)]
+pub struct WithTelety;
+
+#[test]
+fn is_telety() {
+    const { assert!(telety::util::is_telety!(crate::WithTelety)) };
+    const { assert!(!telety::util::is_telety!(self::NoMacro)) };
+}
+
+struct _Value;
+macro_rules! _Value {
+    ($($tokens:tt)*) => {
+        $($tokens)*
+    };
+}
+use _Value as Value;
+
+#[test]
+fn try_invoke_expression() {
+    use telety_macro::try_invoke;
+
+    assert_eq!(try_invoke!(expr => self::Value!(1 + 1); 0), 2);
+    assert_eq!(try_invoke!(expr => self::NoMacro!(1 + 1); 0), 0);
+}
+
+trait Selected {
+    type Ty;
+}
+
+impl<T> Selected for Option<T> {
+    // Type position inside an impl block with generic parameters
+    type Ty = <telety::util::Select<{ telety::util::is_telety!(crate::WithTelety) }> as telety::util::Choose<
+        T,
+        (),
+    >>::Output;
+}
+
+#[test]
+fn select_type() {
+    let selected: <Option<u8> as Selected>::Ty = 1u8;
+    assert_eq!(selected, 1);
+}
+
+#[test]
+fn apply_in_expression() {
+    assert_eq!(
+        telety_test_macros::path_or!(crate::WithTelety, "none"),
+        ":: fallback :: WithTelety"
+    );
+    assert_eq!(telety_test_macros::path_or!(self::NoMacro, "none"), "none");
+}
+
+#[test]
+fn apply_in_type() {
+    let with: telety_test_macros::select_type!(crate::WithTelety, u8, &'static str) = 1;
+    let without: telety_test_macros::select_type!(self::NoMacro, u8, &'static str) = "none";
+    assert_eq!((with, without), (1, "none"));
+}
+
+#[test]
+fn in_type_requires_when_telety() {
+    use quote::{ToTokens as _, format_ident};
+
+    let needle = format_ident!("__needle");
+    let tokens = telety::v0::PATH
+        .apply(syn::parse_quote!(crate::WithTelety), needle.clone(), needle)
+        .in_type()
+        .into_token_stream();
+
+    let error: syn::Macro = syn::parse2(tokens).unwrap();
+    assert!(
+        error
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "compile_error")
+    );
+    assert!(
+        error
+            .tokens
+            .to_string()
+            .contains("Type position is only supported with Apply::when_telety")
+    );
+}
+
+mod forwarded {
+    telety_test_macros::path_const!(WITH, crate::WithTelety);
+    telety_test_macros::path_const!(WITHOUT, crate::NoMacro);
+    telety_test_macros::path_const!(WITH_AGAIN, crate::WithTelety);
+
+    // Identical applications in sibling modules
+    pub mod first {
+        telety_test_macros::path_const!(WITHOUT, crate::NoMacro);
+    }
+    pub mod second {
+        telety_test_macros::path_const!(WITHOUT, crate::NoMacro);
+    }
+}
+
+#[test]
+fn auto_macro_forwarding() {
+    // Each application generates its own forwarding macro, so they can be used in the same crate
+    assert_eq!(forwarded::WITH, ":: fallback :: WithTelety");
+    assert_eq!(forwarded::WITHOUT, "none");
+    assert_eq!(forwarded::WITH_AGAIN, ":: fallback :: WithTelety");
+    assert_eq!(forwarded::first::WITHOUT, "none");
+    assert_eq!(forwarded::second::WITHOUT, "none");
+}
+
+#[test]
+fn auto_macro_forwarding_deterministic() {
+    use quote::{ToTokens as _, format_ident, quote};
+
+    let apply = || {
+        let needle = format_ident!("__needle");
+        telety::v0::PATH
+            .apply(
+                syn::parse_quote!(crate::MaybeTelety),
+                needle.clone(),
+                quote!(struct Found(#needle);),
+            )
+            .with_fallback(quote!(
+                struct NotFound;
+            ))
+            .with_auto_macro_forwarding()
+            .with_downgrade()
+            .into_token_stream()
+            .to_string()
+    };
+
+    // The output does not depend on other expansions
+    assert_eq!(apply(), apply());
+}
+
+#[test]
+fn fallback_chain_error() {
+    use quote::{ToTokens as _, format_ident};
+    use syn::parse::Parser as _;
+
+    let apply = || {
+        let needle = format_ident!("__needle");
+        telety::v0::PATH
+            .apply(syn::parse_quote!(crate::NotTelety), needle.clone(), needle)
+            .with_fallback_chain([syn::parse_quote!(crate::proxies::NotTelety)])
+    };
+
+    // The tokens following the candidates of the `try_invoke!`
+    let fallback = |tokens: proc_macro2::TokenStream| {
+        let try_invoke: syn::Macro = syn::parse2(tokens).unwrap();
+        (|input: syn::parse::ParseStream| {
+            if input.peek(syn::Ident) && input.peek2(syn::Token![=>]) {
+                let _expr: syn::Ident = input.parse()?;
+                let _arrow: syn::Token![=>] = input.parse()?;
+            }
+            let _candidate: syn::Macro = input.parse()?;
+            while input.parse::<Option<syn::Token![else]>>()?.is_some() {
+                let _candidate: syn::Macro = input.parse()?;
+            }
+            input.parse::<proc_macro2::TokenStream>()
+        })
+        .parse2(try_invoke.tokens)
+        .unwrap()
+    };
+    let is_compile_error = |mac: &syn::Macro| {
+        mac.path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "compile_error")
+            && mac
+                .tokens
+                .to_string()
+                .contains("`crate::NotTelety` does not have a telety macro")
+    };
+
+    let item: syn::Item = syn::parse2(fallback(apply().into_token_stream())).unwrap();
+    assert!(matches!(&item, syn::Item::Macro(item) if is_compile_error(&item.mac)));
+
+    let expr: syn::Expr =
+        syn::parse2(fallback(apply().in_expression().into_token_stream())).unwrap();
+    assert!(matches!(&expr, syn::Expr::Macro(expr) if is_compile_error(&expr.mac)));
+}
+
+#[test]
+fn diagnostic_fallback() {
+    use quote::{ToTokens as _, format_ident};
+
+    let needle = format_ident!("__needle");
+    let tokens = telety::v0::PATH
+        .apply(syn::parse_quote!(crate::NotTelety), needle.clone(), needle)
+        .with_fallback_chain([syn::parse_quote!(crate::proxies::NotTelety)])
+        .with_diagnostic_fallback()
+        .into_token_stream()
+        .to_string();
+
+    assert!(tokens.contains("compile_error"));
+    assert!(tokens.contains(
+        "`crate::NotTelety` does not have a telety macro (also tried `crate::proxies::NotTelety`)"
+    ));
+    assert!(tokens.contains(r#"proxy = \"crate::NotTelety\""#));
+}
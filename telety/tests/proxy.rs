@@ -0,0 +1,112 @@
+#![cfg(feature = "v2")]
+
+// Stands in for another crate
+#[allow(dead_code)]
+mod third_party {
+    pub struct Map<K, V> {
+        pub len: usize,
+        _entries: Vec<(K, V)>,
+    }
+
+    impl<K, V> Map<K, V> {
+        pub fn new() -> Self {
+            Self {
+                len: 0,
+                _entries: Vec::new(),
+            }
+        }
+    }
+
+    pub struct Wrapper<T>(pub T, usize);
+
+    pub enum Value<T> {
+        Null,
+        One(T),
+        Many { values: Vec<T> },
+    }
+}
+
+mod proxies {
+    use telety::telety;
+
+    // Private fields are omitted
+    #[telety(crate::proxies, proxy = "crate::third_party::Map")]
+    #[non_exhaustive]
+    pub struct Map<K, V> {
+        pub len: usize,
+    }
+
+    #[telety(crate::proxies, proxy = "crate::third_party::Wrapper")]
+    #[non_exhaustive]
+    pub struct Wrapper<T>(pub T);
+
+    #[telety(crate::proxies, proxy = "crate::third_party::Value")]
+    pub enum Value<T> {
+        Null,
+        One(T),
+        Many { values: Vec<T> },
+    }
+
+    // Foreign and non-exhaustive, so only some variants are listed
+    #[telety(crate::proxies, proxy = "std::io::ErrorKind")]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        NotFound,
+        PermissionDenied,
+    }
+
+    #[telety(crate::proxies, proxy = "std::fmt::Debug")]
+    pub trait Debug {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error>;
+    }
+}
+
+#[test]
+fn struct_proxy() {
+    macro_rules! field_names {
+        ({ $($name:ident : $ty:ty),* }) => {
+            [$(stringify!($name)),*]
+        };
+    }
+
+    // The proxy is the original item
+    let map: proxies::Map<u8, String> = third_party::Map::new();
+    assert_eq!(map.len, 0);
+
+    assert_eq!(
+        telety::with_fields!(proxies::Map => field_names!()),
+        ["len"]
+    );
+}
+
+#[test]
+fn enum_proxy() {
+    macro_rules! variant_names {
+        ({ $($variant:ident $fields:tt $(= $discriminant:expr)?),* }) => {
+            [$(stringify!($variant)),*]
+        };
+    }
+
+    let _: proxies::Value<u8> = third_party::Value::One(1);
+
+    assert_eq!(
+        telety::with_variants!(proxies::Value => variant_names!()),
+        ["Null", "One", "Many"]
+    );
+}
+
+#[test]
+fn non_exhaustive_proxy() {
+    macro_rules! variant_names {
+        ({ $($variant:ident $fields:tt $(= $discriminant:expr)?),* }) => {
+            [$(stringify!($variant)),*]
+        };
+    }
+
+    let _: proxies::ErrorKind = std::io::ErrorKind::Other;
+
+    assert_eq!(
+        telety::with_variants!(proxies::ErrorKind => variant_names!()),
+        ["NotFound", "PermissionDenied"]
+    );
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "std-proxies")]
+
+use telety::util::is_telety;
+
+#[test]
+fn proxies() {
+    const { assert!(is_telety!(telety::std::vec::Vec)) };
+    const { assert!(is_telety!(telety::std::collections::HashMap)) };
+    const { assert!(is_telety!(telety::std::fmt::Debug)) };
+
+    // The proxies are re-exports of the original items
+    let _: telety::std::option::Option<u8> = Some(1);
+    let _: telety::std::sync::Arc<str> = std::sync::Arc::from("");
+}
+
+#[cfg(feature = "v2")]
+#[test]
+fn variants() {
+    macro_rules! variant_names {
+        ({ $($variant:ident $fields:tt $(= $discriminant:expr)?),* }) => {
+            [$(stringify!($variant)),*]
+        };
+    }
+
+    assert_eq!(
+        telety::with_variants!(telety::std::result::Result => variant_names!()),
+        ["Ok", "Err"]
+    );
+}
+
+#[telety::telety(crate)]
+pub struct Holder {
+    pub value: Option<u8>,
+    pub map: mine::HashMap,
+}
+
+pub mod mine {
+    /// Not telety, and unrelated to the `std` item with the same name
+    pub struct HashMap;
+}
+
+#[test]
+fn std_proxy_fallback() {
+    use telety_test_macros::std_path_or;
+
+    const PROXY: &str = ":: telety :: std :: option :: Option";
+
+    assert_eq!(std_path_or!(::core::option::Option<u8>, "none"), PROXY);
+    assert_eq!(std_path_or!(std::option::Option<u8>, "none"), PROXY);
+    // Other paths do not name the `std` item
+    assert_eq!(std_path_or!(crate::mine::HashMap, "none"), "none");
+    assert_eq!(
+        std_path_or!(crate::mine::HashMap as crate::mine::HashMap, "none"),
+        "none"
+    );
+}
+
+#[cfg(feature = "v2")]
+#[test]
+fn std_proxy_fallback_fields() {
+    // The field types are aliases, which only have the telety macros of their items.
+    // Alias paths do not name the `std` item, so it must be given.
+    macro_rules! field_paths {
+        ({ value: $value:ty, map: $map:ty }) => {
+            [
+                telety_test_macros::std_path_or!($value as ::core::option::Option, "none"),
+                telety_test_macros::std_path_or!($value, "none"),
+                telety_test_macros::std_path_or!($map, "none"),
+            ]
+        };
+    }
+
+    assert_eq!(
+        telety::with_fields!(crate::Holder => field_paths!()),
+        [":: telety :: std :: option :: Option", "none", "none"]
+    );
+}
@@ -27,9 +27,32 @@ pub enum MyGeneric<T> {
 #[telety(crate::util::types)]
 pub struct A(B, C);
 
+#[telety(crate::util::types)]
+pub struct Unit;
+
+#[repr(u8)]
+#[telety(crate::util::types)]
+pub enum Tagged {
+    A = 1,
+    B(i32) = 2,
+    C { c: Option<u8> } = 4,
+    D,
+}
+
 #[telety(crate::util::types)]
 pub struct B(i32);
 
+#[telety(crate::util::types)]
+pub struct Parent {
+    pub child: Child,
+    pub other: C,
+}
+
+#[telety(crate::util::types)]
+pub struct Child {
+    pub parent: Option<Box<Parent>>,
+}
+
 pub struct C;
 
 pub struct MyEmpty;
@@ -85,3 +108,157 @@ pub trait NeverAlias: PrivateTrait {
 pub trait AliasMultiPathsOnly: PrivateTrait {
     fn f<T: trait_mod::PubTrait>();
 }
+
+/// Documented
+#[derive(Default)]
+#[telety(crate::util::types)]
+#[allow(clippy::all)]
+pub struct Attributed {
+    pub field: Option<i32>,
+    pub other: u8,
+}
+
+#[telety(crate::util::types, helper_attrs = "mix, builder")]
+#[mix(prefix = "helped")]
+pub struct Helpers {
+    #[mix(rename = "x")]
+    #[builder(default)]
+    pub field: i32,
+    pub other: u8,
+}
+
+#[telety(crate::util::types, helper_attrs = "mix")]
+pub enum HelperVariants {
+    #[mix(skip)]
+    A,
+    B(#[mix(rename = "b")] i32),
+}
+
+struct Private;
+
+#[telety(crate::util::types)]
+pub struct Modes {
+    #[telety(skip)]
+    private: Option<Private>,
+    #[telety(builtin)]
+    pub builtin: Vec<B>,
+    pub aliased: Unit,
+}
+
+#[telety(crate::util::types)]
+pub trait ModeTrait<#[telety(alias)] T: PubTrait> {
+    fn f(#[telety(builtin)] value: Option<Simple>);
+}
+
+struct Secret;
+
+#[telety(crate::util::types, export = "public")]
+pub struct Exported {
+    pub visible: Simple,
+    pub(crate) internal: B,
+    hidden: Secret,
+}
+
+#[telety(crate::util::types, export = "public")]
+pub struct ExportedTuple(Secret, pub u8);
+
+#[telety(crate::util::types, export = "public")]
+pub struct ExportedGeneric<T> {
+    pub value: T,
+    pub simple: Simple,
+    hidden: Secret,
+}
+
+#[telety(crate::util::types, export = "public")]
+pub trait ExportedTrait {
+    fn provided(&self) -> u8 {
+        Secret::VALUE
+    }
+}
+
+impl Secret {
+    const VALUE: u8 = 1;
+}
+
+#[cfg(any())]
+pub struct Missing;
+
+#[telety(crate::util::types)]
+pub struct Conditional {
+    pub always: i32,
+    #[cfg(any())]
+    pub disabled: Missing,
+    #[cfg(test)]
+    pub enabled: B,
+    #[cfg_attr(any(), cfg(any()))]
+    pub attributed: u8,
+}
+
+#[telety(crate::util::types)]
+pub enum ConditionalVariants {
+    A,
+    #[cfg(any())]
+    B(Missing),
+}
+
+/// Generates a telety item from within a `macro_rules!` macro
+macro_rules! generate {
+    ($name:ident) => {
+        #[telety($crate::util::types)]
+        pub struct $name(
+            pub $crate::util::types::B,
+            pub Option<$crate::util::types::Unit>,
+        );
+    };
+}
+
+generate!(Generated);
+
+/// An extension which replaces the needle with the stringified item definition
+#[macro_export]
+macro_rules! definition_ext {
+    ([$($definition:tt)*], definition, $needle:ident, $($haystack:tt)*) => {
+        let $needle = stringify!($($definition)*);
+        $($haystack)*
+    };
+}
+
+#[telety(crate::util::types, extensions = "crate::definition_ext")]
+pub struct Extended(pub i32);
+
+/// An item with the macro generated by a telety release without the `versions` command,
+/// which ignores the arguments of commands
+pub struct Released(pub i32);
+
+mod released {
+    #[allow(unused_macros)]
+    macro_rules! Released {
+        (0, path $( ( $($args:tt)* ) )?, $needle:tt, $($haystack:tt)*) => {
+            ::telety::__private::find_and_replace! {
+                $needle,
+                [::commands::util::types::Released],
+                $($haystack)*
+            }
+        };
+        (1, ty $( ( $($args:tt)* ) )?, $needle:tt, $($haystack:tt)*) => {
+            ::telety::__private::find_and_replace! {
+                $needle,
+                [#[telety(::commands::util::types)] pub struct Released(pub i32);],
+                $($haystack)*
+            }
+        };
+        (0 $($tokens:tt)*) => {
+            compile_error!("Expected a command");
+        };
+        (1 $($tokens:tt)*) => {
+            compile_error!("Expected a command");
+        };
+        ($version:literal $($tokens:tt)*) => {
+            compile_error!(concat!("Unsupported version ", stringify!($version)));
+        };
+    }
+    #[allow(unused_imports)]
+    pub(crate) use Released;
+}
+#[allow(unused_imports)]
+pub(crate) use released::Released;
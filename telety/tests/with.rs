@@ -0,0 +1,45 @@
+mod util;
+
+#[test]
+fn ty() {
+    macro_rules! name {
+        ([$(#[$attr:meta])* $vis:vis enum $name:ident $($rest:tt)*]) => {
+            stringify!($name)
+        };
+    }
+
+    assert_eq!(
+        telety::with_ty!(self::util::types::MyEnum => name!()),
+        "MyEnum"
+    );
+}
+
+#[cfg(feature = "v2")]
+#[test]
+fn fields() {
+    macro_rules! field_count {
+        (( $($index:tt : $ty:ty),* ) $extra:literal) => {
+            [$(stringify!($index)),*].len() + $extra
+        };
+    }
+
+    assert_eq!(
+        telety::with_fields!(self::util::types::Simple => field_count!(10)),
+        11
+    );
+}
+
+#[cfg(feature = "v2")]
+#[test]
+fn variants() {
+    macro_rules! discriminants {
+        ({ $($variant:ident $fields:tt $(= $discriminant:expr)?),* }) => {
+            [$($($discriminant)?),*]
+        };
+    }
+
+    // Only 'D' has no explicit discriminant
+    let discriminants: [u8; 3] =
+        telety::with_variants!(self::util::types::Tagged => discriminants!());
+    assert_eq!(discriminants, [1, 2, 4]);
+}